@@ -0,0 +1,433 @@
+//! Buffer adapters for use with the `bytes` traits.
+
+use crate::{ExtractUtf8Error, StrChunk, StrChunkMut, Utf8Decoder};
+
+use bytes::buf::UninitSlice;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use std::str;
+
+/// A cursor over a `StrChunk`, implementing `bytes::Buf`.
+///
+/// `Buf` consumers can advance the buffer by any number of bytes,
+/// which may leave the read position in the middle of a UTF-8 sequence.
+/// `StrChunk` cannot represent such a state, so it does not implement
+/// `Buf` itself; the cursor wraps the string and tracks the read
+/// position instead. The unread remainder can be obtained as a `StrChunk`
+/// when the position is at a char boundary.
+///
+/// # Example
+///
+/// ```rust
+/// # use strchunk::StrChunk;
+/// use bytes::Buf;
+/// use strchunk::buf::Utf8Buf;
+///
+/// let mut buf = Utf8Buf::from(StrChunk::from("Привет"));
+/// assert_eq!(buf.remaining(), 12);
+/// buf.advance(5);
+/// assert!(buf.remaining_chunk().is_none());
+/// buf.advance(1);
+/// assert_eq!(buf.remaining_chunk().unwrap(), "вет");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Utf8Buf {
+    chunk: StrChunk,
+    pos: usize,
+}
+
+impl Utf8Buf {
+    /// Creates a cursor positioned at the start of the string.
+    #[inline]
+    pub fn new(chunk: StrChunk) -> Self {
+        Utf8Buf { chunk, pos: 0 }
+    }
+
+    /// Returns the number of bytes consumed through the cursor.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns true if the read position is at a char boundary.
+    #[inline]
+    pub fn is_char_boundary(&self) -> bool {
+        self.chunk.is_char_boundary(self.pos)
+    }
+
+    /// Returns the unread part of the string, if the read position is
+    /// at a char boundary. Otherwise, returns `None`.
+    ///
+    /// The returned `StrChunk` shares the buffer of the underlying string.
+    pub fn remaining_chunk(&self) -> Option<StrChunk> {
+        if self.is_char_boundary() {
+            Some(self.chunk.slice(self.pos..))
+        } else {
+            None
+        }
+    }
+
+    /// Gets a reference to the underlying string, including
+    /// the consumed part.
+    #[inline]
+    pub fn get_ref(&self) -> &StrChunk {
+        &self.chunk
+    }
+
+    /// Consumes the cursor, returning the underlying string,
+    /// including the consumed part.
+    #[inline]
+    pub fn into_inner(self) -> StrChunk {
+        self.chunk
+    }
+}
+
+impl From<StrChunk> for Utf8Buf {
+    #[inline]
+    fn from(chunk: StrChunk) -> Self {
+        Utf8Buf::new(chunk)
+    }
+}
+
+impl Buf for Utf8Buf {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.chunk.len() - self.pos
+    }
+
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        &self.chunk.as_bytes()[self.pos..]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining(),
+            "cannot advance past the remaining length of {}",
+            self.remaining()
+        );
+        self.pos += cnt;
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(len <= self.remaining(), "`len` greater than remaining");
+        let bytes =
+            Bytes::from(self.chunk.clone()).slice(self.pos..self.pos + len);
+        self.pos += len;
+        bytes
+    }
+}
+
+/// A `bytes::BufMut` target that accumulates UTF-8 text.
+///
+/// `Utf8BufMut` accepts raw bytes written through its `BufMut`
+/// implementation, for example by `AsyncReadExt::read_buf` in Tokio,
+/// and validates them as they are written. The validated text is kept
+/// at the start of the buffer and can be split off as a `StrChunkMut`
+/// without copying. An incomplete UTF-8 sequence at the end of
+/// the written data stays pending until the bytes completing it
+/// are written.
+///
+/// Validation is performed by a `Utf8Decoder`, so each written byte
+/// is examined only once. It stops at an invalid UTF-8 sequence,
+/// which is reported by `split_text`. Bytes written after it are kept
+/// pending until the invalid sequence is removed.
+///
+/// # Example
+///
+/// ```rust
+/// use bytes::BufMut;
+/// use strchunk::buf::Utf8BufMut;
+///
+/// let mut buf = Utf8BufMut::new();
+/// buf.put_slice(b"\xd0\x97\xd0\xb4\xd1\x80\xd0\xb0\xd0");
+/// assert_eq!(buf.as_str(), "Здра");
+/// assert_eq!(buf.pending_bytes(), b"\xd0");
+///
+/// buf.put_slice(b"\xb2\xd1\x81\xd1\x82\xd0\xb2\xd1\x83\xd0\xb9");
+/// let text = buf.split_text().unwrap();
+/// assert_eq!(text, "Здравствуй");
+/// assert!(buf.pending_bytes().is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Utf8BufMut {
+    bytes: BytesMut,
+    // Tracks the validated text at the start of the buffer.
+    decoder: Utf8Decoder,
+    // The length of an invalid sequence following the validated text.
+    error_len: Option<usize>,
+}
+
+impl Utf8BufMut {
+    /// Creates a new empty `Utf8BufMut`.
+    #[inline]
+    pub fn new() -> Self {
+        Utf8BufMut::default()
+    }
+
+    /// Creates a new empty `Utf8BufMut` with the specified capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Utf8BufMut {
+            bytes: BytesMut::with_capacity(capacity),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the number of bytes the buffer can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more bytes to be
+    /// written into the buffer.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.bytes.reserve(additional)
+    }
+
+    /// Returns the validated text.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // Safety: the prefix of the buffer has been validated.
+        unsafe { str::from_utf8_unchecked(&self.bytes[..self.valid_len()]) }
+    }
+
+    /// Returns the written bytes that follow the validated text.
+    ///
+    /// These are an incomplete UTF-8 sequence, or, if an invalid
+    /// sequence has been written, the invalid sequence and all bytes
+    /// written after it.
+    #[inline]
+    pub fn pending_bytes(&self) -> &[u8] {
+        &self.bytes[self.valid_len()..]
+    }
+
+    /// Splits off the validated text, returning it as a `StrChunkMut`.
+    ///
+    /// The pending bytes are retained in the buffer.
+    ///
+    /// # Errors
+    ///
+    /// If an invalid UTF-8 sequence follows the validated text, an error
+    /// value is returned in the same way as by `Utf8Decoder::decode`:
+    /// the validated text is taken out of the buffer and can be
+    /// obtained from the `ExtractUtf8Error` value, and the invalid
+    /// sequence is removed, so that validation resumes with the bytes
+    /// following it. The stream offset of the invalid sequence is
+    /// counted from the start of the content of the buffer.
+    pub fn split_text(&mut self) -> Result<StrChunkMut, ExtractUtf8Error> {
+        let bytes = self.decoder.split_valid(&mut self.bytes);
+        // Safety: the extracted content has been validated
+        let text = unsafe { StrChunkMut::from_utf8_unchecked(bytes) };
+        match self.error_len.take() {
+            None => Ok(text),
+            Some(error_len) => {
+                let err = self.decoder.skip_invalid(
+                    text.freeze(),
+                    &mut self.bytes,
+                    error_len,
+                );
+                self.validate();
+                Err(err)
+            }
+        }
+    }
+
+    #[inline]
+    fn valid_len(&self) -> usize {
+        self.decoder.valid_len()
+    }
+
+    // Returns the length of the invalid sequence following
+    // the validated text, if one has been written.
+    pub(crate) fn invalid_len(&self) -> Option<usize> {
+        self.error_len
+    }
+
+    // Discards the bytes following the validated text.
+    pub(crate) fn discard_pending(&mut self) {
+        let valid_len = self.valid_len();
+        self.bytes.truncate(valid_len);
+        self.decoder.reset_to(valid_len);
+        self.error_len = None;
+    }
+
+    // Substitutes each invalid sequence with a replacement character,
+    // following the same rules as `String::from_utf8_lossy`.
+    pub(crate) fn replace_invalid(&mut self) {
+        const REPLACEMENT: &[u8] = "\u{FFFD}".as_bytes();
+        while let Some(error_len) = self.error_len.take() {
+            let start = self.valid_len();
+            let end = start + error_len;
+            let len = self.bytes.len();
+            // The replacement is never shorter than the invalid sequence
+            let extra = REPLACEMENT.len() - error_len;
+            self.bytes.resize(len + extra, 0);
+            self.bytes.copy_within(end..len, end + extra);
+            self.bytes[start..start + REPLACEMENT.len()]
+                .copy_from_slice(REPLACEMENT);
+            self.decoder.reset_to(start + REPLACEMENT.len());
+            self.validate();
+        }
+    }
+
+    // Validates the bytes written since the last validation, unless
+    // validation has been stopped at an invalid sequence.
+    fn validate(&mut self) {
+        if self.error_len.is_some() {
+            return;
+        }
+        if let Err(error_len) = self.decoder.validate(&self.bytes) {
+            self.error_len = Some(error_len);
+        }
+    }
+}
+
+impl From<StrChunkMut> for Utf8BufMut {
+    /// Converts a `StrChunkMut` into a `Utf8BufMut` with the string
+    /// as the validated text, so that more text can be written after it.
+    fn from(src: StrChunkMut) -> Self {
+        let bytes = BytesMut::from(src);
+        let mut decoder = Utf8Decoder::new();
+        decoder.reset_to(bytes.len());
+        Utf8BufMut {
+            bytes,
+            decoder,
+            error_len: None,
+        }
+    }
+}
+
+unsafe impl BufMut for Utf8BufMut {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.bytes.remaining_mut()
+    }
+
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.bytes.chunk_mut()
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.bytes.advance_mut(cnt);
+        self.validate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_through_buf() {
+        let mut buf = Utf8Buf::from(StrChunk::from("Hello"));
+        assert_eq!(buf.chunk(), b"Hello");
+        assert_eq!(buf.get_u8(), b'H');
+        assert_eq!(buf.position(), 1);
+        assert_eq!(buf.remaining_chunk().unwrap(), "ello");
+        buf.advance(4);
+        assert!(!buf.has_remaining());
+        assert_eq!(buf.remaining_chunk().unwrap(), "");
+        assert_eq!(buf.into_inner(), "Hello");
+    }
+
+    #[test]
+    fn copy_to_bytes_shares_buffer() {
+        let chunk = StrChunk::from("Привет");
+        let mut buf = Utf8Buf::from(chunk.clone());
+        let bytes = buf.copy_to_bytes(3);
+        assert_eq!(bytes, &b"\xD0\x9F\xD1"[..]);
+        assert_eq!(bytes.as_ptr(), chunk.as_ptr());
+        assert!(!buf.is_char_boundary());
+        assert_eq!(buf.remaining_chunk(), None);
+    }
+
+    #[test]
+    fn chain() {
+        let hello = Utf8Buf::from(StrChunk::from("Hello, "));
+        let world = Utf8Buf::from(StrChunk::from("world"));
+        let mut chain = hello.chain(world);
+        let bytes = chain.copy_to_bytes(chain.remaining());
+        assert_eq!(bytes, "Hello, world");
+    }
+
+    #[test]
+    #[should_panic]
+    fn advance_panics_past_end() {
+        Utf8Buf::from(StrChunk::from("Hello")).advance(6);
+    }
+
+    #[test]
+    fn write_fragmented() {
+        let input = "Hello, Привет, 你好, 🦀!";
+        let mut buf = Utf8BufMut::with_capacity(2);
+        let mut text = String::new();
+        for b in input.as_bytes() {
+            buf.put_u8(*b);
+            assert!(buf.pending_bytes().len() < 4);
+            text.push_str(&buf.split_text().unwrap());
+        }
+        assert_eq!(text, input);
+    }
+
+    #[test]
+    fn split_text_reuses_buffer() {
+        let mut buf = Utf8BufMut::with_capacity(64);
+        buf.put_slice("Привет".as_bytes());
+        let ptr = buf.as_str().as_ptr();
+        let text = buf.split_text().unwrap();
+        assert_eq!(text, "Привет");
+        assert_eq!(text.as_ptr(), ptr);
+        assert_eq!(buf.as_str(), "");
+    }
+
+    #[test]
+    fn invalid_sequence() {
+        let mut buf = Utf8BufMut::new();
+        buf.put_slice(b"Hello \xF0\x90\x80Wor");
+        assert_eq!(buf.as_str(), "Hello ");
+        buf.put_slice(b"ld\xE2\x82");
+        assert_eq!(buf.pending_bytes(), b"\xF0\x90\x80World\xE2\x82");
+        let err = buf.split_text().unwrap_err();
+        assert_eq!(err.invalid_bytes(), b"\xF0\x90\x80");
+        assert_eq!(err.into_extracted(), "Hello ");
+        assert_eq!(buf.as_str(), "World");
+        assert_eq!(buf.pending_bytes(), b"\xE2\x82");
+        buf.put_u8(b'\xAC');
+        assert_eq!(buf.split_text().unwrap(), "World€");
+    }
+
+    #[test]
+    fn errors_match_decoder() {
+        let input = b"Hello \xF0\x90\x80World\xE2\x82\xE2\x82\xAC!\xFF";
+        let mut decoder = Utf8Decoder::new();
+        let mut src = BytesMut::new();
+        let mut buf = Utf8BufMut::new();
+        let mut expected = Vec::new();
+        let mut actual = Vec::new();
+        for b in input {
+            src.put_u8(*b);
+            buf.put_u8(*b);
+            while let Err(e) = decoder.decode(&mut src) {
+                expected.push((e.stream_offset(), e.invalid_bytes().to_vec()));
+            }
+            while let Err(e) = buf.split_text() {
+                actual.push((e.stream_offset(), e.invalid_bytes().to_vec()));
+            }
+        }
+        assert_eq!(actual, expected);
+        assert_eq!(actual.len(), 3);
+        assert_eq!(actual[2].0, Some(input.len() as u64 - 1));
+    }
+
+    #[test]
+    fn append_to_string() {
+        let mut buf = Utf8BufMut::from(StrChunkMut::from("Hello"));
+        buf.put_slice(b", world");
+        assert_eq!(buf.split_text().unwrap(), "Hello, world");
+    }
+}
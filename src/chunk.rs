@@ -1,16 +1,22 @@
 use crate::chunk_mut::StrChunkMut;
+use crate::iter::{
+    LinesChunks, MatchIndicesChunks, MatchesChunks, RSplitChunks, SplitChunks,
+    SplitNChunks,
+};
+use crate::pattern::{self, Pattern};
 
-use bytes::{Bytes, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
 use range_split::TakeRange;
 
 use std::borrow::Borrow;
-use std::convert::{TryFrom, TryInto};
+use std::char::REPLACEMENT_CHARACTER;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt::{self, Debug, Display};
 use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
 use std::ops::Deref;
-use std::ops::RangeBounds;
+use std::ops::{Bound, RangeBounds};
 use std::str::{self, Utf8Error};
 
 // macro
@@ -129,16 +135,93 @@ impl StrChunk {
                     }
                     Some(error_len) => {
                         // Invalid UTF-8 encountered
-                        Err(ExtractUtf8Error {
-                            extracted,
-                            error_len,
-                        })
+                        Err(ExtractUtf8Error::new(extracted, &src[..error_len]))
                     }
                 }
             }
         }
     }
 
+    /// Extracts UTF-8 content from a byte buffer, replacing invalid
+    /// sequences with U+FFFD REPLACEMENT CHARACTER.
+    ///
+    /// Like `extract_utf8`, this function extracts the content of `src`
+    /// up to a possibly incomplete UTF-8 sequence at the end, which is
+    /// left in `src`. Each invalid sequence encountered in the input
+    /// is substituted with a replacement character, following the
+    /// practice of replacing maximal subparts of ill-formed sequences
+    /// that is also implemented by `String::from_utf8_lossy`.
+    ///
+    /// If the extracted content is valid UTF-8, it is split off
+    /// the buffer without copying. Otherwise, the returned `StrChunk`
+    /// is allocated to hold the content with the replacements.
+    ///
+    /// At the end of input, a non-empty remainder in `src` is an
+    /// incomplete UTF-8 sequence, which a lossy decoder should substitute
+    /// with a single replacement character.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bytes::BytesMut;
+    /// # use strchunk::StrChunk;
+    /// const TEST_DATA: &[u8] = b"Hello \xF0\x90\x80World\xE2\x82";
+    /// let mut input = BytesMut::from(TEST_DATA);
+    /// let chunk = StrChunk::extract_utf8_lossy(&mut input);
+    /// assert_eq!(chunk, "Hello \u{FFFD}World");
+    /// assert_eq!(input, b"\xE2\x82"[..]);
+    /// ```
+    pub fn extract_utf8_lossy(src: &mut BytesMut) -> StrChunk {
+        let mut err = match StrChunk::extract_utf8(src) {
+            Ok(chunk) => return chunk,
+            Err(e) => e,
+        };
+        let mut buf = StrChunkMut::new();
+        loop {
+            src.advance(err.error_len());
+            let extracted = err.into_extracted();
+            buf.reserve(extracted.len() + REPLACEMENT_CHARACTER.len_utf8());
+            buf.put_str(extracted);
+            buf.put_char(REPLACEMENT_CHARACTER);
+            match StrChunk::extract_utf8(src) {
+                Ok(chunk) => {
+                    buf.reserve(chunk.len());
+                    buf.put_str(chunk);
+                    return buf.freeze();
+                }
+                Err(e) => {
+                    err = e;
+                }
+            }
+        }
+    }
+
+    /// Converts a byte buffer to a `StrChunk`, replacing invalid
+    /// UTF-8 sequences with U+FFFD REPLACEMENT CHARACTER.
+    ///
+    /// Returns the converted string and a flag that is true if any
+    /// replacements have been made. If the content of `bytes` is valid
+    /// UTF-8, the returned `StrChunk` refers to the same buffer without
+    /// copying and the flag is false. Otherwise, a new buffer is allocated
+    /// for the content with the replacements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bytes::Bytes;
+    /// # use strchunk::StrChunk;
+    /// let bytes = Bytes::from_static(b"Hello \xF0\x90\x80World");
+    /// let (chunk, replaced) = StrChunk::from_utf8_lossy(bytes);
+    /// assert_eq!(chunk, "Hello \u{FFFD}World");
+    /// assert!(replaced);
+    /// ```
+    pub fn from_utf8_lossy(bytes: Bytes) -> (StrChunk, bool) {
+        match StrChunk::try_from(bytes) {
+            Ok(chunk) => (chunk, false),
+            Err(e) => (e.into_utf8_lossy(), true),
+        }
+    }
+
     /// Represents the `StrChunk` contents as a string slice.
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -207,6 +290,547 @@ impl StrChunk {
         StrChunk { bytes }
     }
 
+    /// Splits the string into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[0, at)`, and the returned
+    /// `StrChunk` contains elements `[at, len)`.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count
+    /// and sets a few indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let mut a = StrChunk::from("Привет");
+    /// let b = a.split_off(6);
+    ///
+    /// assert_eq!(a, "При");
+    /// assert_eq!(b, "вет");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a UTF-8 code point
+    /// boundary.
+    #[inline]
+    pub fn split_off(&mut self, at: usize) -> StrChunk {
+        self.take_range(at..)
+    }
+
+    /// Splits the string into two at the given index, checking
+    /// the validity of the index.
+    ///
+    /// This is a non-panicking version of `split_off`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `at > len` or if `at` is not on a UTF-8
+    /// code point boundary.
+    #[inline]
+    pub fn try_split_off(
+        &mut self,
+        at: usize,
+    ) -> Result<StrChunk, BoundaryError> {
+        check_boundary(self.as_str(), at)?;
+        Ok(self.split_off(at))
+    }
+
+    /// Splits the string into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[at, len)`, and the returned
+    /// `StrChunk` contains elements `[0, at)`.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count
+    /// and sets a few indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let mut a = StrChunk::from("Привет");
+    /// let b = a.split_to(6);
+    ///
+    /// assert_eq!(a, "вет");
+    /// assert_eq!(b, "При");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a UTF-8 code point
+    /// boundary.
+    #[inline]
+    pub fn split_to(&mut self, at: usize) -> StrChunk {
+        self.take_range(..at)
+    }
+
+    /// Splits the string into two at the given index, checking
+    /// the validity of the index.
+    ///
+    /// This is a non-panicking version of `split_to`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `at > len` or if `at` is not on a UTF-8
+    /// code point boundary.
+    #[inline]
+    pub fn try_split_to(
+        &mut self,
+        at: usize,
+    ) -> Result<StrChunk, BoundaryError> {
+        check_boundary(self.as_str(), at)?;
+        Ok(self.split_to(at))
+    }
+
+    /// Shortens the string, keeping the first `len` bytes and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater or equal to the string's current length,
+    /// this has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not on a UTF-8 code point boundary.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.remove_range(len..);
+        }
+    }
+
+    /// Shortens the string, checking the validity of the new length.
+    ///
+    /// This is a non-panicking version of `truncate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `len` is not on a UTF-8 code point boundary.
+    #[inline]
+    pub fn try_truncate(&mut self, len: usize) -> Result<(), BoundaryError> {
+        if len < self.len() {
+            check_boundary(self.as_str(), len)?;
+            self.remove_range(len..);
+        }
+        Ok(())
+    }
+
+    /// Clears the string, removing all data.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.bytes.clear()
+    }
+
+    /// Attempts to convert `self` into a `StrChunkMut`.
+    ///
+    /// If `self` is the unique reference to its buffer, the conversion
+    /// succeeds without copying, like `Bytes::try_into_mut`. Otherwise,
+    /// `self` is returned unchanged in the `Err` variant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let chunk = StrChunk::from(String::from("Hello"));
+    /// let copy = chunk.clone();
+    /// let chunk = chunk.try_into_mut().unwrap_err();
+    /// drop(copy);
+    /// let mut buf = chunk.try_into_mut().unwrap();
+    /// buf.push_str(", world");
+    /// assert_eq!(buf, "Hello, world");
+    /// ```
+    pub fn try_into_mut(self) -> Result<StrChunkMut, StrChunk> {
+        match self.bytes.try_into_mut() {
+            // Safety: the content of the buffer is valid UTF-8.
+            Ok(bytes) => Ok(unsafe { StrChunkMut::from_utf8_unchecked(bytes) }),
+            Err(bytes) => Err(StrChunk { bytes }),
+        }
+    }
+
+    /// Converts `self` into a `String`.
+    ///
+    /// If `self` is the unique reference to a heap-allocated buffer,
+    /// the allocation is reused for the `String` without copying.
+    /// Otherwise, the content is copied into a new allocation.
+    #[inline]
+    pub fn into_string(self) -> String {
+        let vec = Vec::from(self.bytes);
+        // Safety: the content of the buffer is valid UTF-8.
+        unsafe { String::from_utf8_unchecked(vec) }
+    }
+
+    /// Splits the string into three parts: the part preceding
+    /// the given range, the range itself, and the part following it.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count
+    /// and sets a few indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let a = StrChunk::from("Привет, мир");
+    /// let (head, mid, tail) = a.split_range(4..12);
+    ///
+    /// assert_eq!(head, "Пр");
+    /// assert_eq!(mid, "ивет");
+    /// assert_eq!(tail, ", мир");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string, if its start
+    /// is greater than its end, or if either of its bounds is not on
+    /// a UTF-8 code point boundary.
+    pub fn split_range<R>(self, range: R) -> (StrChunk, StrChunk, StrChunk)
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = bounded_range(self.as_str(), &range);
+        let head = self.bytes.slice(..start);
+        let mid = self.bytes.slice(start..end);
+        let tail = self.bytes.slice(end..);
+        (
+            StrChunk { bytes: head },
+            StrChunk { bytes: mid },
+            StrChunk { bytes: tail },
+        )
+    }
+
+    /// Returns an iterator over substrings of this string, separated by
+    /// a pattern.
+    ///
+    /// This works like `str::split`, but the substrings are yielded as
+    /// `StrChunk` values sharing the buffer of this string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let chunk = StrChunk::from("Mary had a little lamb");
+    /// let words: Vec<StrChunk> = chunk.split_chunks(' ').collect();
+    /// assert_eq!(words, ["Mary", "had", "a", "little", "lamb"]);
+    /// ```
+    pub fn split_chunks<P: Pattern>(&self, pat: P) -> SplitChunks<P> {
+        SplitChunks::new(self.clone(), pat)
+    }
+
+    /// Returns an iterator over substrings of this string, separated by
+    /// a pattern and yielded in reverse order.
+    ///
+    /// This works like `str::rsplit`, but the substrings are yielded as
+    /// `StrChunk` values sharing the buffer of this string.
+    pub fn rsplit_chunks<P: Pattern>(&self, pat: P) -> RSplitChunks<P> {
+        RSplitChunks::new(self.clone(), pat)
+    }
+
+    /// Returns an iterator over substrings of this string, separated by
+    /// a pattern, restricted to returning at most `n` items.
+    ///
+    /// This works like `str::splitn`, but the substrings are yielded as
+    /// `StrChunk` values sharing the buffer of this string.
+    pub fn splitn_chunks<P: Pattern>(
+        &self,
+        n: usize,
+        pat: P,
+    ) -> SplitNChunks<P> {
+        SplitNChunks::new(self.clone(), n, pat)
+    }
+
+    /// Returns an iterator over substrings of this string, each
+    /// terminated by a pattern.
+    ///
+    /// This works like `str::split_terminator`, but the substrings are
+    /// yielded as `StrChunk` values sharing the buffer of this string.
+    pub fn split_terminator_chunks<P: Pattern>(
+        &self,
+        pat: P,
+    ) -> SplitChunks<P> {
+        SplitChunks::terminator(self.clone(), pat)
+    }
+
+    /// Returns an iterator over the matches of a pattern within
+    /// this string.
+    ///
+    /// This works like `str::matches`, but the matches are yielded as
+    /// `StrChunk` values sharing the buffer of this string.
+    pub fn matches_chunks<P: Pattern>(&self, pat: P) -> MatchesChunks<P> {
+        MatchesChunks::new(self.clone(), pat)
+    }
+
+    /// Returns an iterator over the matches of a pattern within
+    /// this string, as well as the byte indices at which the matches
+    /// start.
+    ///
+    /// This works like `str::match_indices`, but the matches are yielded
+    /// as `StrChunk` values sharing the buffer of this string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let chunk = StrChunk::from("abcXXXabcYYYabc");
+    /// let v: Vec<_> = chunk.match_indices_chunks("abc").collect();
+    /// assert_eq!(v, [(0, "abc".into()), (6, "abc".into()), (12, "abc".into())]);
+    /// ```
+    pub fn match_indices_chunks<P: Pattern>(
+        &self,
+        pat: P,
+    ) -> MatchIndicesChunks<P> {
+        MatchIndicesChunks::new(self.clone(), pat)
+    }
+
+    /// Returns an iterator over the lines of this string, each paired
+    /// with the terminator that ended it.
+    ///
+    /// The lines are yielded as `StrChunk` values sharing the buffer
+    /// of this string. See the documentation of `LinesChunks` for
+    /// the recognized line terminators.
+    pub fn lines_chunks(&self) -> LinesChunks {
+        LinesChunks::new(self.clone())
+    }
+
+    /// Returns a `StrChunk` with leading and trailing whitespace removed.
+    ///
+    /// This works like `str::trim`, but the result shares the buffer
+    /// of this string.
+    pub fn trim_chunk(&self) -> StrChunk {
+        self.slice_ref(self.as_str().trim())
+    }
+
+    /// Returns a `StrChunk` with leading whitespace removed.
+    ///
+    /// This works like `str::trim_start`, but the result shares
+    /// the buffer of this string.
+    pub fn trim_start_chunk(&self) -> StrChunk {
+        self.slice_ref(self.as_str().trim_start())
+    }
+
+    /// Returns a `StrChunk` with trailing whitespace removed.
+    ///
+    /// This works like `str::trim_end`, but the result shares
+    /// the buffer of this string.
+    pub fn trim_end_chunk(&self) -> StrChunk {
+        self.slice_ref(self.as_str().trim_end())
+    }
+
+    /// Returns a `StrChunk` with all prefixes and suffixes that match
+    /// a pattern repeatedly removed.
+    ///
+    /// This works like `str::trim_matches`, but the result shares
+    /// the buffer of this string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let chunk = StrChunk::from("xxHelloxx");
+    /// assert_eq!(chunk.trim_matches_chunk('x'), "Hello");
+    /// ```
+    pub fn trim_matches_chunk<P: Pattern>(&self, mut pat: P) -> StrChunk {
+        self.slice_ref(pattern::trim_matches(self.as_str(), &mut pat))
+    }
+
+    /// Returns a `StrChunk` with all prefixes that match a pattern
+    /// repeatedly removed.
+    ///
+    /// This works like `str::trim_start_matches`, but the result shares
+    /// the buffer of this string.
+    pub fn trim_start_matches_chunk<P: Pattern>(&self, mut pat: P) -> StrChunk {
+        self.slice_ref(pattern::trim_start_matches(self.as_str(), &mut pat))
+    }
+
+    /// Returns a `StrChunk` with all suffixes that match a pattern
+    /// repeatedly removed.
+    ///
+    /// This works like `str::trim_end_matches`, but the result shares
+    /// the buffer of this string.
+    pub fn trim_end_matches_chunk<P: Pattern>(&self, mut pat: P) -> StrChunk {
+        self.slice_ref(pattern::trim_end_matches(self.as_str(), &mut pat))
+    }
+
+    /// Returns a `StrChunk` with the prefix removed.
+    ///
+    /// This works like `str::strip_prefix`, but the result shares
+    /// the buffer of this string. If the string does not start with
+    /// `prefix`, returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let chunk = StrChunk::from("foo:bar");
+    /// assert_eq!(chunk.strip_prefix_chunk("foo:").unwrap(), "bar");
+    /// assert_eq!(chunk.strip_prefix_chunk("bar"), None);
+    /// ```
+    pub fn strip_prefix_chunk<P: Pattern>(
+        &self,
+        mut prefix: P,
+    ) -> Option<StrChunk> {
+        let s = pattern::strip_prefix(self.as_str(), &mut prefix)?;
+        Some(self.slice_ref(s))
+    }
+
+    /// Returns a `StrChunk` with the suffix removed.
+    ///
+    /// This works like `str::strip_suffix`, but the result shares
+    /// the buffer of this string. If the string does not end with
+    /// `suffix`, returns `None`.
+    pub fn strip_suffix_chunk<P: Pattern>(
+        &self,
+        mut suffix: P,
+    ) -> Option<StrChunk> {
+        let s = pattern::strip_suffix(self.as_str(), &mut suffix)?;
+        Some(self.slice_ref(s))
+    }
+
+    /// Splits the string on the first occurrence of the specified
+    /// delimiter and returns the parts before and after the delimiter.
+    ///
+    /// This works like `str::split_once`, but the parts share the buffer
+    /// of this string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let chunk = StrChunk::from("key=value=1");
+    /// let (key, value) = chunk.split_once_chunks('=').unwrap();
+    /// assert_eq!(key, "key");
+    /// assert_eq!(value, "value=1");
+    /// ```
+    pub fn split_once_chunks<P: Pattern>(
+        &self,
+        mut delimiter: P,
+    ) -> Option<(StrChunk, StrChunk)> {
+        let s = self.as_str();
+        let (start, end) = delimiter.find_in(s)?;
+        Some((self.slice_ref(&s[..start]), self.slice_ref(&s[end..])))
+    }
+
+    /// Splits the string on the last occurrence of the specified
+    /// delimiter and returns the parts before and after the delimiter.
+    ///
+    /// This works like `str::rsplit_once`, but the parts share the buffer
+    /// of this string.
+    pub fn rsplit_once_chunks<P: Pattern>(
+        &self,
+        mut delimiter: P,
+    ) -> Option<(StrChunk, StrChunk)> {
+        let s = self.as_str();
+        let (start, end) = delimiter.rfind_in(s)?;
+        Some((self.slice_ref(&s[..start]), self.slice_ref(&s[end..])))
+    }
+
+    /// Removes leading and trailing whitespace from the string in place.
+    ///
+    /// This is an `O(1)` operation with regard to memory: the view
+    /// of the buffer is narrowed without allocating.
+    pub fn trim_in_place(&mut self) {
+        self.narrow_to(str::trim)
+    }
+
+    /// Removes leading whitespace from the string in place.
+    pub fn trim_start_in_place(&mut self) {
+        self.narrow_to(str::trim_start)
+    }
+
+    /// Removes trailing whitespace from the string in place.
+    pub fn trim_end_in_place(&mut self) {
+        self.narrow_to(str::trim_end)
+    }
+
+    /// Repeatedly removes all prefixes and suffixes that match
+    /// a pattern from the string in place.
+    pub fn trim_matches_in_place<P: Pattern>(&mut self, mut pat: P) {
+        self.narrow_to(|s| pattern::trim_matches(s, &mut pat))
+    }
+
+    /// Repeatedly removes all prefixes that match a pattern from
+    /// the string in place.
+    pub fn trim_start_matches_in_place<P: Pattern>(&mut self, mut pat: P) {
+        self.narrow_to(|s| pattern::trim_start_matches(s, &mut pat))
+    }
+
+    /// Repeatedly removes all suffixes that match a pattern from
+    /// the string in place.
+    pub fn trim_end_matches_in_place<P: Pattern>(&mut self, mut pat: P) {
+        self.narrow_to(|s| pattern::trim_end_matches(s, &mut pat))
+    }
+
+    /// Removes the prefix from the string in place.
+    ///
+    /// Returns `true` if the string started with `prefix` and it has been
+    /// removed, `false` if the string has been left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunk;
+    ///
+    /// let mut chunk = StrChunk::from("foo:bar");
+    /// assert!(chunk.strip_prefix_in_place("foo:"));
+    /// assert_eq!(chunk, "bar");
+    /// assert!(!chunk.strip_prefix_in_place("foo:"));
+    /// ```
+    pub fn strip_prefix_in_place<P: Pattern>(&mut self, mut prefix: P) -> bool {
+        let mut stripped = false;
+        self.narrow_to(|s| match pattern::strip_prefix(s, &mut prefix) {
+            Some(rest) => {
+                stripped = true;
+                rest
+            }
+            None => s,
+        });
+        stripped
+    }
+
+    /// Removes the suffix from the string in place.
+    ///
+    /// Returns `true` if the string ended with `suffix` and it has been
+    /// removed, `false` if the string has been left unchanged.
+    pub fn strip_suffix_in_place<P: Pattern>(&mut self, mut suffix: P) -> bool {
+        let mut stripped = false;
+        self.narrow_to(|s| match pattern::strip_suffix(s, &mut suffix) {
+            Some(rest) => {
+                stripped = true;
+                rest
+            }
+            None => s,
+        });
+        stripped
+    }
+
+    // Narrows the view of the buffer to the substring selected by
+    // the function.
+    fn narrow_to(&mut self, f: impl FnOnce(&str) -> &str) {
+        let s = self.as_str();
+        let sub = f(s);
+        let start = sub.as_ptr() as usize - s.as_ptr() as usize;
+        let end = start + sub.len();
+        self.bytes.truncate(end);
+        self.bytes.advance(start);
+    }
+
+    /// Wraps a byte buffer without checking that its content
+    /// is valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The content of `bytes` must be valid UTF-8.
+    #[inline]
+    pub(crate) unsafe fn from_utf8_unchecked(bytes: Bytes) -> StrChunk {
+        StrChunk { bytes }
+    }
+
     pub(crate) fn take_range<R>(&mut self, range: R) -> StrChunk
     where
         R: RangeBounds<usize> + Debug,
@@ -225,6 +849,79 @@ impl StrChunk {
         assert_str_range!(self.as_str(), range);
         self.bytes.remove_range(range);
     }
+
+    // Takes out a range that may be bounded at both ends, splicing
+    // the remaining parts together. The remainder needs to be copied
+    // if the range is in the middle of the string.
+    pub(crate) fn take_bounded_range<R>(&mut self, range: R) -> StrChunk
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = bounded_range(self.as_str(), &range);
+        let bytes = self.bytes.slice(start..end);
+        self.splice_out(start, end);
+        StrChunk { bytes }
+    }
+
+    pub(crate) fn remove_bounded_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = bounded_range(self.as_str(), &range);
+        self.splice_out(start, end);
+    }
+
+    fn splice_out(&mut self, start: usize, end: usize) {
+        if start == 0 {
+            self.bytes.advance(end);
+        } else if end == self.bytes.len() {
+            self.bytes.truncate(start);
+        } else {
+            let len = self.bytes.len() - (end - start);
+            let mut buf = BytesMut::with_capacity(len);
+            buf.extend_from_slice(&self.bytes[..start]);
+            buf.extend_from_slice(&self.bytes[end..]);
+            self.bytes = buf.freeze();
+        }
+    }
+}
+
+// Resolves the bounds of a range within a string, panicking
+// if the range is not valid for splitting the string.
+pub(crate) fn bounded_range<R>(s: &str, range: &R) -> (usize, usize)
+where
+    R: RangeBounds<usize> + Debug,
+{
+    assert_str_range!(s, *range);
+    resolve_range(s.len(), range)
+}
+
+// Resolves the bounds of a range within a sequence of the given length,
+// panicking if the range is out of bounds.
+pub(crate) fn resolve_range<R>(len: usize, range: &R) -> (usize, usize)
+where
+    R: RangeBounds<usize> + Debug,
+{
+    let start = match range.start_bound() {
+        Bound::Included(&index) => Some(index),
+        Bound::Excluded(&index) => index.checked_add(1),
+        Bound::Unbounded => Some(0),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&index) => index.checked_add(1),
+        Bound::Excluded(&index) => Some(index),
+        Bound::Unbounded => Some(len),
+    };
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) if end <= len => (start, end),
+        _ => panic!("range {:?} is out of bounds of length {}", range, len),
+    };
+    assert!(
+        start <= end,
+        "range {:?} has the start greater than the end",
+        range
+    );
+    (start, end)
 }
 
 impl Debug for StrChunk {
@@ -265,17 +962,24 @@ impl From<Box<str>> for StrChunk {
 }
 
 impl TryFrom<Bytes> for StrChunk {
-    type Error = Utf8Error;
+    type Error = FromUtf8Error<Bytes>;
     fn try_from(bytes: Bytes) -> Result<Self, Self::Error> {
-        str::from_utf8(&bytes)?;
-        Ok(StrChunk { bytes })
+        match str::from_utf8(&bytes) {
+            Ok(_) => Ok(StrChunk { bytes }),
+            Err(error) => Err(FromUtf8Error::new(bytes, error)),
+        }
     }
 }
 
 impl TryFrom<BytesMut> for StrChunk {
-    type Error = Utf8Error;
+    type Error = FromUtf8Error<BytesMut>;
     fn try_from(bytes: BytesMut) -> Result<Self, Self::Error> {
-        bytes.freeze().try_into()
+        match str::from_utf8(&bytes) {
+            Ok(_) => Ok(StrChunk {
+                bytes: bytes.freeze(),
+            }),
+            Err(error) => Err(FromUtf8Error::new(bytes, error)),
+        }
     }
 }
 
@@ -289,7 +993,7 @@ impl From<StrChunk> for Bytes {
 impl From<StrChunk> for String {
     #[inline]
     fn from(src: StrChunk) -> String {
-        String::from(src.as_str())
+        src.into_string()
     }
 }
 
@@ -335,11 +1039,12 @@ impl FromIterator<char> for StrChunk {
     }
 }
 
-/// An error returned by `StrChunk::extract_utf8`.
+/// An error returned by `StrChunk::extract_utf8` and `Utf8Decoder`.
 ///
 /// `ExtractUtf8Error` indicates an invalid UTF-8 sequence encountered
 /// in the input and provides information necessary for lossy recovery
-/// of an incremental UTF-8 decoding stream.
+/// of an incremental UTF-8 decoding stream, as well as details of the
+/// invalid sequence for diagnostics.
 ///
 /// # Example
 ///
@@ -360,9 +1065,30 @@ impl FromIterator<char> for StrChunk {
 pub struct ExtractUtf8Error {
     extracted: StrChunk,
     error_len: usize,
+    // The bytes of the invalid sequence, which is never
+    // longer than 3 bytes.
+    invalid_bytes: [u8; 3],
+    stream_offset: Option<u64>,
 }
 
 impl ExtractUtf8Error {
+    pub(crate) fn new(extracted: StrChunk, invalid: &[u8]) -> Self {
+        let error_len = invalid.len();
+        let mut invalid_bytes = [0; 3];
+        invalid_bytes[..error_len].copy_from_slice(invalid);
+        ExtractUtf8Error {
+            extracted,
+            error_len,
+            invalid_bytes,
+            stream_offset: None,
+        }
+    }
+
+    pub(crate) fn with_stream_offset(mut self, offset: u64) -> Self {
+        self.stream_offset = Some(offset);
+        self
+    }
+
     /// Length of the invalid byte sequence.
     /// A lossy decoding procedure should advance the reading position
     /// by the returned amount using the `advance` method of the input buffer
@@ -371,6 +1097,28 @@ impl ExtractUtf8Error {
         self.error_len
     }
 
+    /// Returns the bytes of the invalid sequence.
+    pub fn invalid_bytes(&self) -> &[u8] {
+        &self.invalid_bytes[..self.error_len]
+    }
+
+    /// Returns the offset of the invalid sequence in the input buffer,
+    /// which is the length of the valid UTF-8 content extracted before it.
+    ///
+    /// This corresponds to `Utf8Error::valid_up_to`.
+    pub fn valid_up_to(&self) -> usize {
+        self.extracted.len()
+    }
+
+    /// Returns the offset of the invalid sequence from the beginning
+    /// of the decoded stream.
+    ///
+    /// The stream offset is only known for errors reported
+    /// by `Utf8Decoder`; `None` is returned otherwise.
+    pub fn stream_offset(&self) -> Option<u64> {
+        self.stream_offset
+    }
+
     /// Consumes `self` to obtain the string content extracted up to
     /// the encountered invalid sequence.
     pub fn into_extracted(self) -> StrChunk {
@@ -380,12 +1128,176 @@ impl ExtractUtf8Error {
 
 impl Display for ExtractUtf8Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "invalid UTF-8 sequence in input")
+        f.write_str("invalid UTF-8 sequence ")?;
+        for b in self.invalid_bytes() {
+            write!(f, "\\x{:02X}", b)?;
+        }
+        write!(f, " at offset {}", self.valid_up_to())?;
+        if let Some(offset) = self.stream_offset {
+            write!(f, " (stream offset {})", offset)?;
+        }
+        Ok(())
     }
 }
 
 impl Error for ExtractUtf8Error {}
 
+/// An error returned by the non-panicking splitting methods of
+/// `StrChunk` and `StrChunkMut`.
+///
+/// `BoundaryError` indicates that the requested index is out of bounds
+/// of the string or does not fall on a UTF-8 code point boundary.
+///
+/// # Example
+///
+/// ```rust
+/// # use strchunk::StrChunk;
+/// let mut chunk = StrChunk::from("Привет");
+/// let err = chunk.try_split_to(3).unwrap_err();
+/// assert_eq!(err.index(), 3);
+/// assert!(!err.is_out_of_bounds());
+/// assert_eq!(chunk, "Привет");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoundaryError {
+    index: usize,
+    len: usize,
+}
+
+impl BoundaryError {
+    /// Returns the index that failed the check.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns true if the index is out of bounds of the string,
+    /// false if it is within the string, but not on a UTF-8 code point
+    /// boundary.
+    pub fn is_out_of_bounds(&self) -> bool {
+        self.index > self.len
+    }
+}
+
+impl Display for BoundaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_out_of_bounds() {
+            write!(
+                f,
+                "byte index {} is out of bounds of string of length {}",
+                self.index, self.len
+            )
+        } else {
+            write!(f, "byte index {} is not a char boundary", self.index)
+        }
+    }
+}
+
+impl Error for BoundaryError {}
+
+pub(crate) fn check_boundary(
+    s: &str,
+    index: usize,
+) -> Result<(), BoundaryError> {
+    if s.is_char_boundary(index) {
+        Ok(())
+    } else {
+        Err(BoundaryError {
+            index,
+            len: s.len(),
+        })
+    }
+}
+
+/// An error returned by the `TryFrom` conversions of byte buffers
+/// into `StrChunk` and `StrChunkMut`.
+///
+/// Like `std::string::FromUtf8Error`, this error type gives back
+/// the byte buffer that failed the UTF-8 validation, so that it can be
+/// processed otherwise without having been cloned.
+///
+/// # Example
+///
+/// ```rust
+/// # use bytes::Bytes;
+/// # use strchunk::StrChunk;
+/// # use std::convert::TryFrom;
+/// let bytes = Bytes::from_static(b"Hello \xF0\x90\x80World");
+/// let err = StrChunk::try_from(bytes).unwrap_err();
+/// assert_eq!(err.utf8_error().valid_up_to(), 6);
+/// let bytes = err.into_bytes();
+/// assert_eq!(bytes, b"Hello \xF0\x90\x80World"[..]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromUtf8Error<B> {
+    bytes: B,
+    error: Utf8Error,
+}
+
+impl<B> FromUtf8Error<B> {
+    pub(crate) fn new(bytes: B, error: Utf8Error) -> Self {
+        FromUtf8Error { bytes, error }
+    }
+
+    /// Returns a slice of the bytes that were attempted to convert.
+    pub fn as_bytes(&self) -> &[u8]
+    where
+        B: AsRef<[u8]>,
+    {
+        self.bytes.as_ref()
+    }
+
+    /// Returns the byte buffer that was attempted to convert.
+    pub fn into_bytes(self) -> B {
+        self.bytes
+    }
+
+    /// Returns the `Utf8Error` with details of the conversion failure.
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+
+    /// Converts the bytes into a `StrChunk`, replacing invalid UTF-8
+    /// sequences with U+FFFD REPLACEMENT CHARACTER.
+    ///
+    /// As the bytes are known to be invalid, the returned `StrChunk`
+    /// is allocated anew.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bytes::Bytes;
+    /// # use strchunk::StrChunk;
+    /// # use std::convert::TryFrom;
+    /// let bytes = Bytes::from_static(b"Hello \xF0\x90\x80World");
+    /// let (chunk, replaced) = match StrChunk::try_from(bytes) {
+    ///     Ok(chunk) => (chunk, false),
+    ///     Err(e) => (e.into_utf8_lossy(), true),
+    /// };
+    /// assert_eq!(chunk, "Hello \u{FFFD}World");
+    /// assert!(replaced);
+    /// ```
+    pub fn into_utf8_lossy(self) -> StrChunk
+    where
+        B: AsRef<[u8]>,
+    {
+        String::from_utf8_lossy(self.bytes.as_ref())
+            .into_owned()
+            .into()
+    }
+}
+
+impl<B> Display for FromUtf8Error<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<B: Debug> Error for FromUtf8Error<B> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -395,4 +1307,255 @@ mod tests {
         let s = StrChunk::from_static("Hello");
         assert_eq!(s.as_bytes(), b"Hello");
     }
+
+    mod extract_utf8_error {
+        use super::*;
+
+        #[test]
+        fn details() {
+            let mut buf = BytesMut::from(&b"Hello \xF0\x90\x80World"[..]);
+            let err = StrChunk::extract_utf8(&mut buf).unwrap_err();
+            assert_eq!(err.error_len(), 3);
+            assert_eq!(err.invalid_bytes(), b"\xF0\x90\x80");
+            assert_eq!(err.valid_up_to(), 6);
+            assert_eq!(err.stream_offset(), None);
+            assert_eq!(
+                err.to_string(),
+                "invalid UTF-8 sequence \\xF0\\x90\\x80 at offset 6"
+            );
+        }
+    }
+
+    mod split {
+        use super::*;
+
+        #[test]
+        fn split_off() {
+            let mut a = StrChunk::from("Привет");
+            let b = a.split_off(12);
+            assert_eq!(a, "Привет");
+            assert_eq!(b, "");
+            let b = a.split_off(0);
+            assert_eq!(a, "");
+            assert_eq!(b, "Привет");
+        }
+
+        #[test]
+        #[should_panic]
+        fn split_off_panics_on_split_utf8() {
+            StrChunk::from("Привет").split_off(3);
+        }
+
+        #[test]
+        #[should_panic]
+        fn split_to_panics_on_oob() {
+            StrChunk::from("Hello").split_to(6);
+        }
+
+        #[test]
+        fn try_split() {
+            let mut a = StrChunk::from("Привет");
+            let err = a.try_split_off(13).unwrap_err();
+            assert!(err.is_out_of_bounds());
+            let err = a.try_split_to(5).unwrap_err();
+            assert!(!err.is_out_of_bounds());
+            assert_eq!(a, "Привет");
+            assert_eq!(a.try_split_to(6).unwrap(), "При");
+            assert_eq!(a, "вет");
+        }
+
+        #[test]
+        fn truncate() {
+            let mut a = StrChunk::from("Привет");
+            a.truncate(20);
+            assert_eq!(a, "Привет");
+            assert!(a.try_truncate(3).is_err());
+            a.truncate(4);
+            assert_eq!(a, "Пр");
+            a.clear();
+            assert!(a.is_empty());
+        }
+
+        #[test]
+        #[should_panic]
+        fn truncate_panics_on_split_utf8() {
+            StrChunk::from("Привет").truncate(3);
+        }
+    }
+
+    #[test]
+    fn try_into_mut() {
+        let chunk = StrChunk::from(String::from("Привет"));
+        let ptr = chunk.as_ptr();
+        let shared = chunk.clone();
+        let chunk = chunk.try_into_mut().unwrap_err();
+        drop(shared);
+        let buf = chunk.try_into_mut().unwrap();
+        assert_eq!(buf, "Привет");
+        assert_eq!(buf.as_ptr(), ptr);
+        let chunk = StrChunk::from_static("Привет");
+        assert_eq!(chunk.try_into_mut().unwrap_err(), "Привет");
+    }
+
+    #[test]
+    fn into_string() {
+        let chunk = StrChunk::from(String::from("Привет"));
+        let ptr = chunk.as_ptr();
+        let s = chunk.into_string();
+        assert_eq!(s, "Привет");
+        assert_eq!(s.as_ptr(), ptr);
+        let chunk = StrChunk::from(String::from("Привет"));
+        let shared = chunk.clone();
+        assert_eq!(String::from(chunk), "Привет");
+        assert_eq!(shared, "Привет");
+    }
+
+    mod trim {
+        use super::*;
+
+        #[test]
+        fn trim_shares_buffer() {
+            let chunk = StrChunk::from("  Привет ");
+            let trimmed = chunk.trim_chunk();
+            assert_eq!(trimmed, "Привет");
+            assert_eq!(trimmed.as_ptr(), chunk[2..].as_ptr());
+            assert_eq!(chunk.trim_start_chunk(), "Привет ");
+            assert_eq!(chunk.trim_end_chunk(), "  Привет");
+        }
+
+        #[test]
+        fn trim_matches() {
+            let chunk = StrChunk::from("123foo1bar123");
+            let is_digit = |c: char| c.is_numeric();
+            assert_eq!(chunk.trim_matches_chunk(is_digit), "foo1bar");
+            assert_eq!(chunk.trim_start_matches_chunk("12"), "3foo1bar123");
+            assert_eq!(chunk.trim_end_matches_chunk(['2', '3']), "123foo1bar1");
+            assert_eq!(chunk.trim_start_matches_chunk(""), chunk);
+        }
+
+        #[test]
+        fn strip() {
+            let chunk = StrChunk::from("foo:bar");
+            assert_eq!(chunk.strip_prefix_chunk('f').unwrap(), "oo:bar");
+            assert_eq!(chunk.strip_suffix_chunk(":bar").unwrap(), "foo");
+            assert_eq!(chunk.strip_suffix_chunk('x'), None);
+            assert_eq!(chunk.strip_prefix_chunk("").unwrap(), chunk);
+        }
+
+        #[test]
+        fn split_once() {
+            let chunk = StrChunk::from("a=b=c");
+            let (a, bc) = chunk.split_once_chunks('=').unwrap();
+            assert_eq!((a, bc), ("a".into(), "b=c".into()));
+            let (ab, c) = chunk.rsplit_once_chunks("=").unwrap();
+            assert_eq!((ab, c), ("a=b".into(), "c".into()));
+            assert_eq!(chunk.split_once_chunks(','), None);
+        }
+
+        #[test]
+        fn in_place() {
+            let mut chunk = StrChunk::from(" \t xxПриветxx \n");
+            let ptr = chunk[5..].as_ptr();
+            chunk.trim_start_in_place();
+            chunk.trim_end_in_place();
+            chunk.trim_matches_in_place('x');
+            assert_eq!(chunk, "Привет");
+            assert_eq!(chunk.as_ptr(), ptr);
+            assert!(chunk.strip_prefix_in_place("При"));
+            assert!(!chunk.strip_suffix_in_place("При"));
+            assert!(chunk.strip_suffix_in_place('т'));
+            assert_eq!(chunk, "ве");
+            chunk.trim_start_matches_in_place('в');
+            chunk.trim_end_matches_in_place('е');
+            assert!(chunk.is_empty());
+            chunk.trim_in_place();
+            assert!(chunk.is_empty());
+        }
+    }
+
+    mod try_from {
+        use super::*;
+
+        #[test]
+        fn bytes() {
+            let chunk = StrChunk::try_from(Bytes::from("Привет")).unwrap();
+            assert_eq!(chunk, "Привет");
+        }
+
+        #[test]
+        fn invalid_bytes() {
+            let bytes = Bytes::from_static(b"Hello\xD0");
+            let ptr = bytes.as_ptr();
+            let err = StrChunk::try_from(bytes).unwrap_err();
+            assert_eq!(err.utf8_error().valid_up_to(), 5);
+            assert_eq!(err.as_bytes(), b"Hello\xD0");
+            assert_eq!(err.into_bytes().as_ptr(), ptr);
+        }
+
+        #[test]
+        fn invalid_bytes_mut() {
+            let bytes = BytesMut::from(&b"Hello\xD0"[..]);
+            let ptr = bytes.as_ptr();
+            let err = StrChunk::try_from(bytes).unwrap_err();
+            assert_eq!(err.into_bytes().as_ptr(), ptr);
+        }
+    }
+
+    mod from_utf8_lossy {
+        use super::*;
+
+        #[test]
+        fn valid_input_is_not_copied() {
+            let bytes = Bytes::from("Привет");
+            let ptr = bytes.as_ptr();
+            let (chunk, replaced) = StrChunk::from_utf8_lossy(bytes);
+            assert_eq!(chunk, "Привет");
+            assert_eq!(chunk.as_ptr(), ptr);
+            assert!(!replaced);
+        }
+
+        #[test]
+        fn replaces_invalid_sequences() {
+            const TEST_DATA: &[u8] = b"\xFFHello\xE1\x80 \xF0\x90\x80";
+            let (chunk, replaced) =
+                StrChunk::from_utf8_lossy(Bytes::from(TEST_DATA));
+            assert_eq!(chunk, "\u{FFFD}Hello\u{FFFD} \u{FFFD}");
+            assert!(replaced);
+        }
+    }
+
+    mod extract_utf8_lossy {
+        use super::*;
+
+        #[test]
+        fn valid_input_is_not_copied() {
+            let mut buf = BytesMut::from("Привет".as_bytes());
+            let ptr = buf.as_ptr();
+            let chunk = StrChunk::extract_utf8_lossy(&mut buf);
+            assert_eq!(chunk, "Привет");
+            assert_eq!(chunk.as_ptr(), ptr);
+        }
+
+        #[test]
+        fn replaces_maximal_subparts() {
+            const TEST_DATA: &[u8] =
+                b"\x61\xF1\x80\x80\xE1\x80\xC2\x62\x80\x63\x80\xBF\x64";
+            let mut buf = BytesMut::from(TEST_DATA);
+            let chunk = StrChunk::extract_utf8_lossy(&mut buf);
+            assert_eq!(chunk, String::from_utf8_lossy(TEST_DATA));
+            assert_eq!(
+                chunk,
+                "a\u{FFFD}\u{FFFD}\u{FFFD}b\u{FFFD}c\u{FFFD}\u{FFFD}d"
+            );
+            assert!(buf.is_empty());
+        }
+
+        #[test]
+        fn leaves_incomplete_sequence() {
+            let mut buf = BytesMut::from(&b"\xFFa\xF0\x90"[..]);
+            let chunk = StrChunk::extract_utf8_lossy(&mut buf);
+            assert_eq!(chunk, "\u{FFFD}a");
+            assert_eq!(buf, b"\xF0\x90"[..]);
+        }
+    }
 }
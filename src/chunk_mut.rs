@@ -1,4 +1,7 @@
-use crate::chunk::StrChunk;
+use crate::chunk::{
+    bounded_range, check_boundary, BoundaryError, FromUtf8Error, StrChunk,
+};
+use crate::iter::Drain;
 
 use bytes::{BufMut, Bytes, BytesMut};
 use range_split::TakeRange;
@@ -11,7 +14,7 @@ use std::iter::{FromIterator, Iterator};
 use std::ops::RangeBounds;
 use std::ops::{Deref, DerefMut};
 use std::slice;
-use std::str::{self, Utf8Error};
+use std::str;
 
 // macro
 use range_split::assert_str_range;
@@ -94,7 +97,198 @@ impl StrChunkMut {
     /// across threads.
     #[inline]
     pub fn freeze(self) -> StrChunk {
-        StrChunk::from(self)
+        // Safety: the content of the buffer is valid UTF-8.
+        unsafe { StrChunk::from_utf8_unchecked(self.bytes.freeze()) }
+    }
+
+    /// Removes the string content from the buffer, returning it
+    /// in a new `StrChunkMut` handle.
+    ///
+    /// Afterwards, `self` will be empty, but will retain any additional
+    /// capacity that it had before the operation.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count
+    /// and sets a few indices.
+    #[inline]
+    pub fn split(&mut self) -> StrChunkMut {
+        let bytes = self.bytes.split();
+        StrChunkMut { bytes }
+    }
+
+    /// Absorbs a `StrChunkMut` that was previously split off.
+    ///
+    /// If the two `StrChunkMut` objects were previously contiguous,
+    /// i.e. if `other` was created by calling `split_off` on this
+    /// object, then this is an `O(1)` operation that just decreases
+    /// a reference count and sets a few indices. Otherwise, this method
+    /// appends a copy of the content of `other` to this string,
+    /// growing the buffer as needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunkMut;
+    ///
+    /// let mut buf = StrChunkMut::from("Привет, мир");
+    /// let tail = buf.split_off(12);
+    /// assert_eq!(buf, "Привет");
+    /// buf.unsplit(tail);
+    /// assert_eq!(buf, "Привет, мир");
+    /// ```
+    #[inline]
+    pub fn unsplit(&mut self, other: StrChunkMut) {
+        self.bytes.unsplit(other.bytes)
+    }
+
+    /// Splits the string into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[0, at)`, and the returned
+    /// `StrChunkMut` contains elements `[at, capacity)`.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count
+    /// and sets a few indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a UTF-8 code point
+    /// boundary.
+    #[inline]
+    pub fn split_off(&mut self, at: usize) -> StrChunkMut {
+        self.take_range(at..)
+    }
+
+    /// Splits the string into two at the given index, checking
+    /// the validity of the index.
+    ///
+    /// This is a non-panicking version of `split_off`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `at > len` or if `at` is not on a UTF-8
+    /// code point boundary.
+    #[inline]
+    pub fn try_split_off(
+        &mut self,
+        at: usize,
+    ) -> Result<StrChunkMut, BoundaryError> {
+        check_boundary(self.as_str(), at)?;
+        Ok(self.split_off(at))
+    }
+
+    /// Splits the string into two at the given index.
+    ///
+    /// Afterwards `self` contains elements `[at, len)`, and the returned
+    /// `StrChunkMut` contains elements `[0, at)`.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count
+    /// and sets a few indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len` or if `at` is not on a UTF-8 code point
+    /// boundary.
+    #[inline]
+    pub fn split_to(&mut self, at: usize) -> StrChunkMut {
+        self.take_range(..at)
+    }
+
+    /// Splits the string into two at the given index, checking
+    /// the validity of the index.
+    ///
+    /// This is a non-panicking version of `split_to`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `at > len` or if `at` is not on a UTF-8
+    /// code point boundary.
+    #[inline]
+    pub fn try_split_to(
+        &mut self,
+        at: usize,
+    ) -> Result<StrChunkMut, BoundaryError> {
+        check_boundary(self.as_str(), at)?;
+        Ok(self.split_to(at))
+    }
+
+    /// Shortens the string, keeping the first `len` bytes and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater or equal to the string's current length,
+    /// this has no effect. The capacity of the buffer is not changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not on a UTF-8 code point boundary.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.remove_range(len..);
+        }
+    }
+
+    /// Shortens the string, checking the validity of the new length.
+    ///
+    /// This is a non-panicking version of `truncate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `len` is not on a UTF-8 code point boundary.
+    #[inline]
+    pub fn try_truncate(&mut self, len: usize) -> Result<(), BoundaryError> {
+        if len < self.len() {
+            check_boundary(self.as_str(), len)?;
+            self.remove_range(len..);
+        }
+        Ok(())
+    }
+
+    /// Clears the string, removing all data. The capacity of the buffer
+    /// is not changed.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.bytes.clear()
+    }
+
+    /// Splits the string into three parts: the part preceding
+    /// the given range, the range itself, and the part following it.
+    ///
+    /// This is an `O(1)` operation that just increases the reference count
+    /// and sets a few indices. The spare capacity of the buffer goes
+    /// to the last part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunkMut;
+    ///
+    /// let a = StrChunkMut::from("Привет, мир");
+    /// let (head, mid, tail) = a.split_range(4..12);
+    ///
+    /// assert_eq!(head, "Пр");
+    /// assert_eq!(mid, "ивет");
+    /// assert_eq!(tail, ", мир");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string, if its start
+    /// is greater than its end, or if either of its bounds is not on
+    /// a UTF-8 code point boundary.
+    pub fn split_range<R>(
+        mut self,
+        range: R,
+    ) -> (StrChunkMut, StrChunkMut, StrChunkMut)
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = bounded_range(self.as_str(), &range);
+        let tail = self.bytes.split_off(end);
+        let mid = self.bytes.split_off(start);
+        (
+            self,
+            StrChunkMut { bytes: mid },
+            StrChunkMut { bytes: tail },
+        )
     }
 
     /// Represents the `StrChunkMut` contents as a string slice.
@@ -143,6 +337,257 @@ impl StrChunkMut {
         self.bytes.put_slice(string.as_ref().as_bytes())
     }
 
+    /// Appends a Unicode character to the end of the string.
+    ///
+    /// Unlike `put_char`, this method grows the buffer if its remaining
+    /// capacity is not sufficient. The capacity is grown at least twofold
+    /// on reallocation, so that appending is amortized `O(1)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunkMut;
+    ///
+    /// let mut buf = StrChunkMut::new();
+    /// buf.push('П');
+    /// buf.push('!');
+    /// assert_eq!(buf, "П!");
+    /// ```
+    #[inline]
+    pub fn push(&mut self, c: char) {
+        self.grow_for(c.len_utf8());
+        self.put_char(c);
+    }
+
+    /// Appends a string slice to the end of the string.
+    ///
+    /// The buffer is grown if its remaining capacity is not sufficient,
+    /// in the same way as described for `push`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunkMut;
+    ///
+    /// let mut buf = StrChunkMut::from("Hello");
+    /// buf.push_str(", world");
+    /// assert_eq!(buf, "Hello, world");
+    /// ```
+    #[inline]
+    pub fn push_str(&mut self, string: &str) {
+        self.grow_for(string.len());
+        self.bytes.extend_from_slice(string.as_bytes());
+    }
+
+    /// Appends a string slice to the end of the string.
+    ///
+    /// This is a counterpart of `BytesMut::extend_from_slice` and works
+    /// the same as `push_str`.
+    #[inline]
+    pub fn extend_from_str(&mut self, string: &str) {
+        self.push_str(string)
+    }
+
+    /// Inserts a character into the string at a byte position.
+    ///
+    /// This is an `O(n)` operation as it requires copying every element
+    /// past the position. The buffer is grown as needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the string's length, or if it does
+    /// not lie on a UTF-8 code point boundary.
+    #[inline]
+    pub fn insert(&mut self, idx: usize, ch: char) {
+        self.insert_str(idx, ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Inserts a string slice into the string at a byte position.
+    ///
+    /// This is an `O(n)` operation as it requires copying every element
+    /// past the position. The buffer is grown as needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunkMut;
+    ///
+    /// let mut buf = StrChunkMut::from("Привет");
+    /// buf.insert_str(6, "-");
+    /// assert_eq!(buf, "При-вет");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the string's length, or if it does
+    /// not lie on a UTF-8 code point boundary.
+    #[inline]
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        self.replace_range(idx..idx, string)
+    }
+
+    /// Removes a character from the string at a byte position and
+    /// returns it.
+    ///
+    /// This is an `O(n)` operation as it requires copying every element
+    /// past the removed character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than or equal to the string's length,
+    /// or if it does not lie on a UTF-8 code point boundary.
+    pub fn remove(&mut self, idx: usize) -> char {
+        let ch = match self[idx..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+        self.remove_bounded_range(idx..idx + ch.len_utf8());
+        ch
+    }
+
+    /// Removes the last character from the string and returns it,
+    /// or `None` if the string is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().next_back()?;
+        let len = self.len() - ch.len_utf8();
+        self.bytes.truncate(len);
+        Some(ch)
+    }
+
+    /// Retains only the characters specified by the predicate.
+    ///
+    /// In other words, removes all characters `c` such that `f(c)`
+    /// returns `false`. This method operates in place, visiting each
+    /// character exactly once in the original order, and preserves
+    /// the order of the retained characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunkMut;
+    ///
+    /// let mut buf = StrChunkMut::from("f_o_ob_ar");
+    /// buf.retain(|c| c != '_');
+    /// assert_eq!(buf, "foobar");
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(char) -> bool,
+    {
+        // The retained characters are moved towards the start of
+        // the buffer, leaving possibly invalid UTF-8 behind them.
+        // The guard truncates the buffer to the retained content
+        // when done, or if `f` panics.
+        struct Guard<'a> {
+            bytes: &'a mut BytesMut,
+            retained: usize,
+        }
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                self.bytes.truncate(self.retained);
+            }
+        }
+
+        let len = self.bytes.len();
+        let mut guard = Guard {
+            bytes: &mut self.bytes,
+            retained: 0,
+        };
+        let mut pos = 0;
+        while pos < len {
+            // Safety: the content past `pos` has not been modified
+            // and is valid UTF-8.
+            let rest = unsafe { str::from_utf8_unchecked(&guard.bytes[pos..]) };
+            let ch = rest.chars().next().unwrap();
+            let ch_len = ch.len_utf8();
+            if f(ch) {
+                let retained = guard.retained;
+                guard.bytes.copy_within(pos..pos + ch_len, retained);
+                guard.retained += ch_len;
+            }
+            pos += ch_len;
+        }
+    }
+
+    /// Removes the specified range from the string, returning
+    /// the removed characters as an iterator.
+    ///
+    /// The range is removed when the iterator is dropped, even if
+    /// it has not been fully consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunkMut;
+    ///
+    /// let mut buf = StrChunkMut::from("α is alpha, β is beta");
+    /// let beta_offset = buf.find('β').unwrap();
+    /// let drained: String = buf.drain(..beta_offset).collect();
+    /// assert_eq!(drained, "α is alpha, ");
+    /// assert_eq!(buf, "β is beta");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string, if its start
+    /// is greater than its end, or if either of its bounds is not on
+    /// a UTF-8 code point boundary.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_>
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = bounded_range(self.as_str(), &range);
+        Drain::new(self, start, end)
+    }
+
+    /// Replaces the specified range in the string with the given
+    /// string slice.
+    ///
+    /// The length of the replacement does not need to match the length
+    /// of the range. The buffer is grown as needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use strchunk::StrChunkMut;
+    ///
+    /// let mut buf = StrChunkMut::from("α is alpha, β is beta");
+    /// let beta_offset = buf.find('β').unwrap();
+    /// buf.replace_range(..beta_offset, "Α is capital alpha; ");
+    /// assert_eq!(buf, "Α is capital alpha; β is beta");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string, if its start
+    /// is greater than its end, or if either of its bounds is not on
+    /// a UTF-8 code point boundary.
+    pub fn replace_range<R>(&mut self, range: R, replace_with: &str)
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = bounded_range(self.as_str(), &range);
+        let len = self.bytes.len();
+        let new_end = start + replace_with.len();
+        if new_end > end {
+            self.grow_for(new_end - end);
+            self.bytes.resize(len + (new_end - end), 0);
+        }
+        self.bytes.copy_within(end..len, new_end);
+        self.bytes[start..new_end].copy_from_slice(replace_with.as_bytes());
+        self.bytes.truncate(new_end + (len - end));
+    }
+
+    // Makes room for `additional` more bytes, at least doubling
+    // the capacity if the buffer has to be reallocated.
+    fn grow_for(&mut self, additional: usize) {
+        let spare = self.bytes.capacity() - self.bytes.len();
+        if spare < additional {
+            self.bytes.reserve(additional.max(self.bytes.capacity()));
+        }
+    }
+
     fn from_iter_chars<T>(mut iter: T) -> Self
     where
         T: Iterator<Item = char>,
@@ -203,6 +648,17 @@ impl StrChunkMut {
         }
     }
 
+    /// Wraps a byte buffer without checking that its content
+    /// is valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The content of `bytes` must be valid UTF-8.
+    #[inline]
+    pub(crate) unsafe fn from_utf8_unchecked(bytes: BytesMut) -> StrChunkMut {
+        StrChunkMut { bytes }
+    }
+
     pub(crate) fn take_range<R>(&mut self, range: R) -> StrChunkMut
     where
         R: RangeBounds<usize> + Debug,
@@ -221,6 +677,29 @@ impl StrChunkMut {
         assert_str_range!(self.as_str(), range);
         self.bytes.remove_range(range);
     }
+
+    // Takes out a range that may be bounded at both ends, splicing
+    // the remaining parts together.
+    pub(crate) fn take_bounded_range<R>(&mut self, range: R) -> StrChunkMut
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = bounded_range(self.as_str(), &range);
+        let mut bytes = self.bytes.split_off(start);
+        let tail = bytes.split_off(end - start);
+        self.bytes.unsplit(tail);
+        StrChunkMut { bytes }
+    }
+
+    pub(crate) fn remove_bounded_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = bounded_range(self.as_str(), &range);
+        let len = self.bytes.len() - (end - start);
+        self.bytes.copy_within(end.., start);
+        self.bytes.truncate(len);
+    }
 }
 
 impl Debug for StrChunkMut {
@@ -236,6 +715,20 @@ impl Display for StrChunkMut {
     }
 }
 
+impl fmt::Write for StrChunkMut {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
 impl<'a> From<&'a str> for StrChunkMut {
     #[inline]
     fn from(src: &'a str) -> StrChunkMut {
@@ -244,12 +737,14 @@ impl<'a> From<&'a str> for StrChunkMut {
 }
 
 impl TryFrom<BytesMut> for StrChunkMut {
-    type Error = Utf8Error;
+    type Error = FromUtf8Error<BytesMut>;
 
     #[inline]
     fn try_from(bytes: BytesMut) -> Result<Self, Self::Error> {
-        str::from_utf8(&bytes)?;
-        Ok(StrChunkMut { bytes })
+        match str::from_utf8(&bytes) {
+            Ok(_) => Ok(StrChunkMut { bytes }),
+            Err(e) => Err(FromUtf8Error::new(bytes, e)),
+        }
     }
 }
 
@@ -379,6 +874,209 @@ mod tests {
         assert_eq!(s.as_bytes(), b"Hello");
     }
 
+    #[test]
+    fn freeze() {
+        let s = StrChunkMut::from("Hello");
+        let ptr = s.as_ptr();
+        let s = s.freeze();
+        assert_eq!(s, "Hello");
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn split() {
+        let mut a = StrChunkMut::from("Привет");
+        let b = a.split();
+        assert_eq!(a, "");
+        assert_eq!(b, "Привет");
+    }
+
+    #[test]
+    fn unsplit_contiguous() {
+        let mut a = StrChunkMut::from("Привет, мир");
+        let ptr = a.as_ptr();
+        let b = a.split_off(6);
+        a.unsplit(b);
+        assert_eq!(a, "Привет, мир");
+        assert_eq!(a.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn unsplit_copies_non_contiguous() {
+        let mut a = StrChunkMut::from("Привет");
+        a.unsplit(StrChunkMut::from(", мир"));
+        assert_eq!(a, "Привет, мир");
+    }
+
+    #[test]
+    fn split_off_and_to() {
+        let mut a = StrChunkMut::from("Привет, мир");
+        let b = a.split_off(12);
+        assert_eq!(a, "Привет");
+        assert_eq!(b, ", мир");
+        let c = a.split_to(6);
+        assert_eq!(c, "При");
+        assert_eq!(a, "вет");
+    }
+
+    #[test]
+    fn try_split() {
+        let mut a = StrChunkMut::from("Привет");
+        assert!(a.try_split_off(13).unwrap_err().is_out_of_bounds());
+        assert!(!a.try_split_to(5).unwrap_err().is_out_of_bounds());
+        assert_eq!(a, "Привет");
+    }
+
+    #[test]
+    fn truncate() {
+        let mut a = StrChunkMut::from("Привет");
+        a.truncate(20);
+        assert_eq!(a, "Привет");
+        assert!(a.try_truncate(3).is_err());
+        a.truncate(4);
+        assert_eq!(a, "Пр");
+        a.clear();
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn truncate_panics_on_split_utf8() {
+        StrChunkMut::from("Привет").truncate(3);
+    }
+
+    #[test]
+    fn push_grows() {
+        let mut buf = StrChunkMut::with_capacity(1);
+        buf.push('П');
+        buf.push_str("ривет");
+        buf.extend_from_str(", мир");
+        assert_eq!(buf, "Привет, мир");
+        assert!(buf.capacity() >= buf.len());
+    }
+
+    #[test]
+    fn push_amortizes_growth() {
+        let mut buf = StrChunkMut::new();
+        let mut reallocations = 0;
+        let mut capacity = buf.capacity();
+        for _ in 0..1000 {
+            buf.push('🦀');
+            if buf.capacity() != capacity {
+                reallocations += 1;
+                capacity = buf.capacity();
+            }
+        }
+        assert_eq!(buf.len(), 4000);
+        assert!(reallocations <= 12, "{} reallocations", reallocations);
+    }
+
+    #[test]
+    fn write_fmt() {
+        use std::fmt::Write;
+
+        let greeting = "Привет";
+        let mut buf = StrChunkMut::new();
+        write!(buf, "{}, {}!", greeting, 42).unwrap();
+        buf.write_char('\n').unwrap();
+        assert_eq!(buf, "Привет, 42!\n");
+    }
+
+    #[test]
+    fn insert() {
+        let mut buf = StrChunkMut::from("Прет");
+        buf.insert(4, 'и');
+        buf.insert_str(6, "в");
+        buf.insert(0, '¡');
+        buf.insert_str(buf.len(), "!");
+        assert_eq!(buf, "¡Привет!");
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_panics_on_split_utf8() {
+        StrChunkMut::from("Привет").insert(1, 'x');
+    }
+
+    #[test]
+    fn remove_and_pop() {
+        let mut buf = StrChunkMut::from("Привет");
+        assert_eq!(buf.remove(2), 'р');
+        assert_eq!(buf.pop(), Some('т'));
+        assert_eq!(buf, "Пиве");
+        buf.clear();
+        assert_eq!(buf.pop(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_panics_at_end() {
+        StrChunkMut::from("Hello").remove(5);
+    }
+
+    #[test]
+    fn retain() {
+        let mut buf = StrChunkMut::from("П-р-и-в-е-т 🦀");
+        buf.retain(|c| c != '-');
+        assert_eq!(buf, "Привет 🦀");
+        buf.retain(|c| c.is_ascii());
+        assert_eq!(buf, " ");
+    }
+
+    #[test]
+    fn retain_unwind_leaves_valid_utf8() {
+        let mut buf = StrChunkMut::from("aбвгд");
+        let res =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                buf.retain(|c| match c {
+                    'в' => panic!("boom"),
+                    c => c != 'a',
+                })
+            }));
+        assert!(res.is_err());
+        assert_eq!(buf, "б");
+    }
+
+    #[test]
+    fn drain() {
+        let mut buf = StrChunkMut::from("Привет, мир");
+        let drained: String = buf.drain(6..14).collect();
+        assert_eq!(drained, "вет, ");
+        assert_eq!(buf, "Примир");
+        let mut drain = buf.drain(6..);
+        assert_eq!(drain.as_str(), "мир");
+        assert_eq!(drain.next_back(), Some('р'));
+        assert_eq!(drain.next(), Some('м'));
+        drop(drain);
+        assert_eq!(buf, "При");
+    }
+
+    #[test]
+    #[should_panic]
+    fn drain_panics_on_split_utf8() {
+        StrChunkMut::from("Привет").drain(..3);
+    }
+
+    #[test]
+    fn replace_range() {
+        let mut buf = StrChunkMut::from("Привет, мир");
+        buf.replace_range(14.., "world");
+        assert_eq!(buf, "Привет, world");
+        buf.replace_range(..12, "Hi");
+        assert_eq!(buf, "Hi, world");
+        buf.replace_range(4..=8, "мир");
+        assert_eq!(buf, "Hi, мир");
+    }
+
+    #[test]
+    fn try_from_invalid_bytes_mut() {
+        let bytes = BytesMut::from(&b"Hello\xD0"[..]);
+        let ptr = bytes.as_ptr();
+        let err = StrChunkMut::try_from(bytes).unwrap_err();
+        assert_eq!(err.utf8_error().error_len(), None);
+        assert_eq!(err.into_bytes().as_ptr(), ptr);
+    }
+
     #[test]
     fn as_bytes_mut_via_deref_mut() {
         let mut s = StrChunkMut::from("Hello");
@@ -0,0 +1,626 @@
+//! Codecs for framed I/O with `tokio-util`.
+//!
+//! This module is available with the `tokio-util` feature enabled.
+
+use crate::reader::{decode_utf8, incomplete_utf8_error};
+use crate::{StrChunk, Utf8Decoder};
+
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use std::cmp;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io;
+
+/// A codec for UTF-8 text frames.
+///
+/// The `Decoder` implementation produces frames of complete UTF-8
+/// content available in the read buffer as `StrChunk` values,
+/// split from the buffer without copying. A UTF-8 sequence that is
+/// incomplete at the end of the buffer is left to be completed by
+/// further input.
+///
+/// The `Encoder` implementation writes string data into the output buffer.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> std::io::Result<()> {
+/// use futures::stream::TryStreamExt;
+/// use strchunk::codec::Utf8Codec;
+/// use tokio_util::codec::FramedRead;
+///
+/// let input: &[u8] = "Привет".as_bytes();
+/// let frames = FramedRead::new(input, Utf8Codec::new());
+/// let chunks: Vec<_> = frames.try_collect().await?;
+/// assert_eq!(chunks.concat(), "Привет");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Utf8Codec {
+    decoder: Utf8Decoder,
+}
+
+impl Utf8Codec {
+    /// Creates a new `Utf8Codec`.
+    pub fn new() -> Self {
+        Utf8Codec::default()
+    }
+}
+
+impl Decoder for Utf8Codec {
+    type Item = StrChunk;
+    type Error = io::Error;
+
+    /// Decodes a frame of UTF-8 content from the buffer.
+    ///
+    /// # Errors
+    ///
+    /// An invalid UTF-8 sequence in the input results in an error
+    /// of kind `InvalidData` wrapping an `ExtractUtf8Error` that holds
+    /// the text decoded before the invalid sequence; the invalid
+    /// sequence is removed from the buffer.
+    fn decode(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<Option<StrChunk>, io::Error> {
+        let chunk = decode_utf8(&mut self.decoder, src)?;
+        if chunk.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(chunk))
+        }
+    }
+
+    /// Decodes the last frames of UTF-8 content at the end of input.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors reported by `decode`, an incomplete
+    /// UTF-8 sequence at the end of input is reported as an error
+    /// of kind `InvalidData`.
+    fn decode_eof(
+        &mut self,
+        buf: &mut BytesMut,
+    ) -> Result<Option<StrChunk>, io::Error> {
+        match self.decode(buf)? {
+            Some(chunk) => Ok(Some(chunk)),
+            None if buf.is_empty() => Ok(None),
+            None => {
+                buf.clear();
+                self.decoder.reset();
+                Err(incomplete_utf8_error())
+            }
+        }
+    }
+}
+
+impl<T> Encoder<T> for Utf8Codec
+where
+    T: AsRef<str>,
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> io::Result<()> {
+        let s = item.as_ref();
+        dst.reserve(s.len());
+        dst.put_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// A codec for text lines.
+///
+/// The `Decoder` implementation splits the read buffer into lines
+/// terminated by `\n` and produces each line, without the line
+/// terminator, as a `StrChunk` sharing the buffer memory.
+/// By default, a `\r` preceding the `\n` is also stripped.
+/// UTF-8 validation is performed incrementally, so that the bytes of
+/// a line received across multiple reads are only validated once.
+/// A line containing invalid UTF-8 is reported as an error of kind
+/// `InvalidData` and skipped, so that decoding can continue with
+/// the next line.
+///
+/// The `Encoder` implementation writes a line of string data followed
+/// by `\n` into the output buffer.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), strchunk::codec::LinesCodecError> {
+/// use futures::stream::TryStreamExt;
+/// use strchunk::codec::LinesCodec;
+/// use tokio_util::codec::FramedRead;
+///
+/// let input: &[u8] = b"Hello\r\nworld\n";
+/// let frames = FramedRead::new(input, LinesCodec::new());
+/// let lines: Vec<_> = frames.try_collect().await?;
+/// assert_eq!(lines, ["Hello", "world"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LinesCodec {
+    // The index in the buffer from which to resume searching for
+    // the line terminator.
+    next_index: usize,
+    decoder: Utf8Decoder,
+    max_length: usize,
+    strip_cr: bool,
+    discard_overlong: bool,
+    is_discarding: bool,
+}
+
+impl LinesCodec {
+    /// Creates a new `LinesCodec` with no limit on the line length.
+    ///
+    /// Note that without a limit, a malicious or malfunctioning peer can
+    /// make the read buffer grow without bound by sending input without
+    /// line terminators. Consider using `new_with_max_length` instead.
+    pub fn new() -> Self {
+        LinesCodec::new_with_max_length(usize::MAX)
+    }
+
+    /// Creates a new `LinesCodec` with a maximum line length.
+    ///
+    /// The length is counted in bytes, not including the line terminator.
+    /// By default, the decoder fails with
+    /// `LinesCodecError::MaxLineLengthExceeded` on a longer line,
+    /// skipping the line so that decoding can resume with the next one;
+    /// `discard_overlong` can be used to skip such lines silently.
+    pub fn new_with_max_length(max_length: usize) -> Self {
+        LinesCodec {
+            next_index: 0,
+            decoder: Utf8Decoder::new(),
+            max_length,
+            strip_cr: true,
+            discard_overlong: false,
+            is_discarding: false,
+        }
+    }
+
+    /// Returns the maximum line length for decoding.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// Sets whether a `\r` preceding the terminating `\n` is stripped
+    /// from decoded lines. This is enabled by default.
+    pub fn strip_cr(mut self, enabled: bool) -> Self {
+        self.strip_cr = enabled;
+        self
+    }
+
+    /// Sets whether lines exceeding the maximum length are silently
+    /// discarded.
+    ///
+    /// In either case, the decoder drops the input up to and including
+    /// the next line terminator and resumes decoding from the following
+    /// line. If enabled, this is done without failing with an error.
+    /// This is disabled by default.
+    pub fn discard_overlong(mut self, enabled: bool) -> Self {
+        self.discard_overlong = enabled;
+        self
+    }
+
+    fn reset(&mut self) {
+        self.next_index = 0;
+        self.decoder.reset();
+    }
+
+    // Validates the buffer content up to `end`, allowing an incomplete
+    // UTF-8 sequence at the end if `complete` is false.
+    fn validate(
+        &mut self,
+        buf: &[u8],
+        end: usize,
+        complete: bool,
+    ) -> Result<(), LinesCodecError> {
+        match self.decoder.validate(&buf[..end]) {
+            Ok(valid_len) if valid_len == end || !complete => Ok(()),
+            _ => Err(LinesCodecError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid UTF-8 sequence in line",
+            ))),
+        }
+    }
+
+    // Returns the length of the line content in `line`, which may
+    // be incomplete, not counting the terminator and a `\r` that is
+    // or may be stripped.
+    fn content_len(&self, line: &[u8]) -> usize {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        match line.strip_suffix(b"\r") {
+            Some(stripped) if self.strip_cr => stripped.len(),
+            _ => line.len(),
+        }
+    }
+
+    fn take_line(&mut self, buf: &mut BytesMut, len: usize) -> StrChunk {
+        let mut line = buf.split_to(len);
+        if line.last() == Some(&b'\n') {
+            line.truncate(line.len() - 1);
+            if self.strip_cr && line.last() == Some(&b'\r') {
+                line.truncate(line.len() - 1);
+            }
+        }
+        self.reset();
+        // Safety: the line content has been validated as UTF-8
+        // and the line terminator is ASCII.
+        unsafe { StrChunk::from_utf8_unchecked(line.freeze()) }
+    }
+}
+
+impl Default for LinesCodec {
+    fn default() -> Self {
+        LinesCodec::new()
+    }
+}
+
+impl Decoder for LinesCodec {
+    type Item = StrChunk;
+    type Error = LinesCodecError;
+
+    fn decode(
+        &mut self,
+        buf: &mut BytesMut,
+    ) -> Result<Option<StrChunk>, LinesCodecError> {
+        loop {
+            // Do not search past the maximum line length plus
+            // the terminator, including a `\r` that would be stripped.
+            let terminator_len = if self.strip_cr { 2 } else { 1 };
+            let read_to = cmp::min(
+                self.max_length.saturating_add(terminator_len),
+                buf.len(),
+            );
+            let newline_offset = buf[self.next_index..read_to]
+                .iter()
+                .position(|b| *b == b'\n');
+            match (self.is_discarding, newline_offset) {
+                (true, Some(offset)) => {
+                    buf.advance(self.next_index + offset + 1);
+                    self.is_discarding = false;
+                    self.reset();
+                }
+                (true, None) => {
+                    buf.advance(read_to);
+                    self.reset();
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                }
+                (false, Some(offset)) => {
+                    let line_len = self.next_index + offset + 1;
+                    if self.content_len(&buf[..line_len]) > self.max_length {
+                        buf.advance(line_len);
+                        self.reset();
+                        if !self.discard_overlong {
+                            return Err(LinesCodecError::MaxLineLengthExceeded);
+                        }
+                        continue;
+                    }
+                    if let Err(e) = self.validate(buf, line_len, true) {
+                        // Skip the invalid line, so that decoding can
+                        // resume with the next one.
+                        buf.advance(line_len);
+                        self.reset();
+                        return Err(e);
+                    }
+                    return Ok(Some(self.take_line(buf, line_len)));
+                }
+                (false, None)
+                    if self.content_len(&buf[..read_to]) > self.max_length =>
+                {
+                    // Discard the rest of the overlong line
+                    // up to the terminator.
+                    self.reset();
+                    self.is_discarding = true;
+                    if !self.discard_overlong {
+                        return Err(LinesCodecError::MaxLineLengthExceeded);
+                    }
+                }
+                (false, None) => {
+                    if let Err(e) = self.validate(buf, read_to, false) {
+                        // Discard the rest of the invalid line
+                        // up to the terminator.
+                        self.reset();
+                        self.is_discarding = true;
+                        return Err(e);
+                    }
+                    self.next_index = read_to;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn decode_eof(
+        &mut self,
+        buf: &mut BytesMut,
+    ) -> Result<Option<StrChunk>, LinesCodecError> {
+        if let Some(line) = self.decode(buf)? {
+            return Ok(Some(line));
+        }
+        if buf.is_empty() || self.is_discarding {
+            buf.clear();
+            self.is_discarding = false;
+            self.reset();
+            return Ok(None);
+        }
+        // The last line is not terminated
+        if let Err(e) = self.validate(buf, buf.len(), true) {
+            buf.clear();
+            self.reset();
+            return Err(e);
+        }
+        let mut line = self.take_line(buf, buf.len());
+        if self.strip_cr && line.ends_with('\r') {
+            line.remove_range(line.len() - 1..);
+        }
+        Ok(Some(line))
+    }
+}
+
+impl<T> Encoder<T> for LinesCodec
+where
+    T: AsRef<str>,
+{
+    type Error = LinesCodecError;
+
+    fn encode(
+        &mut self,
+        line: T,
+        dst: &mut BytesMut,
+    ) -> Result<(), LinesCodecError> {
+        let line = line.as_ref();
+        dst.reserve(line.len() + 1);
+        dst.put_slice(line.as_bytes());
+        dst.put_u8(b'\n');
+        Ok(())
+    }
+}
+
+/// An error occurring in `LinesCodec`.
+#[derive(Debug)]
+pub enum LinesCodecError {
+    /// The maximum line length was exceeded.
+    MaxLineLengthExceeded,
+    /// An I/O error occurred. Invalid UTF-8 in the input is reported
+    /// as an error of kind `InvalidData`.
+    Io(io::Error),
+}
+
+impl Display for LinesCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinesCodecError::MaxLineLengthExceeded => {
+                write!(f, "maximum line length exceeded")
+            }
+            LinesCodecError::Io(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl Error for LinesCodecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LinesCodecError::MaxLineLengthExceeded => None,
+            LinesCodecError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for LinesCodecError {
+    fn from(e: io::Error) -> Self {
+        LinesCodecError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_split_sequence() {
+        let mut codec = Utf8Codec::new();
+        let mut buf = BytesMut::from(&b"\xd0\x9f\xd1"[..]);
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "П");
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.extend_from_slice(b"\x80");
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "р");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decode_invalid_sequence() {
+        let mut codec = Utf8Codec::new();
+        let mut buf = BytesMut::from(&b"Hello \xF0\x90\x80World"[..]);
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(buf, &b"World"[..]);
+    }
+
+    #[test]
+    fn decode_eof_with_incomplete_sequence() {
+        let mut codec = Utf8Codec::new();
+        let mut buf = BytesMut::from(&b"Hello\xd0"[..]);
+        assert_eq!(codec.decode_eof(&mut buf).unwrap().unwrap(), "Hello");
+        let err = codec.decode_eof(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(codec.decode_eof(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn encode() {
+        let mut codec = Utf8Codec::new();
+        let mut buf = BytesMut::new();
+        codec.encode(StrChunk::from("Hello, "), &mut buf).unwrap();
+        codec.encode("world!", &mut buf).unwrap();
+        assert_eq!(buf, &b"Hello, world!"[..]);
+    }
+
+    mod lines {
+        use super::*;
+
+        #[test]
+        fn decode_lines() {
+            let mut codec = LinesCodec::new();
+            let mut buf = BytesMut::from(&b"Hello\r\nworld\n\nrest"[..]);
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "Hello");
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "world");
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "");
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+            assert_eq!(codec.decode_eof(&mut buf).unwrap().unwrap(), "rest");
+            assert_eq!(codec.decode_eof(&mut buf).unwrap(), None);
+        }
+
+        #[test]
+        fn decode_keeping_cr() {
+            let mut codec = LinesCodec::new().strip_cr(false);
+            let mut buf = BytesMut::from(&b"Hello\r\n"[..]);
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "Hello\r");
+        }
+
+        #[test]
+        fn decode_split_input() {
+            let mut codec = LinesCodec::new();
+            let mut buf = BytesMut::from(&b"\xd0\x9f\xd1"[..]);
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+            buf.extend_from_slice(b"\x80\xd0\xb8\n");
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "При");
+            assert!(buf.is_empty());
+        }
+
+        #[test]
+        fn decode_invalid_utf8() {
+            let mut codec = LinesCodec::new();
+            let mut buf = BytesMut::from(&b"Hello\xd0\n"[..]);
+            match codec.decode(&mut buf) {
+                Err(LinesCodecError::Io(e)) => {
+                    assert_eq!(e.kind(), io::ErrorKind::InvalidData)
+                }
+                res => panic!("unexpected result {:?}", res),
+            }
+        }
+
+        #[test]
+        fn resume_after_invalid_line() {
+            let mut codec = LinesCodec::new();
+            let mut buf = BytesMut::from(&b"bad\xff\ngood\n"[..]);
+            assert!(codec.decode(&mut buf).is_err());
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "good");
+            assert!(buf.is_empty());
+        }
+
+        #[test]
+        fn resume_after_invalid_partial_line() {
+            let mut codec = LinesCodec::new();
+            let mut buf = BytesMut::from(&b"bad\xff"[..]);
+            assert!(codec.decode(&mut buf).is_err());
+            buf.extend_from_slice(b"line\ngood\n");
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "good");
+        }
+
+        #[test]
+        fn decode_eof_with_incomplete_sequence() {
+            let mut codec = LinesCodec::new();
+            let mut buf = BytesMut::from(&b"Hello\xd0"[..]);
+            assert!(codec.decode_eof(&mut buf).is_err());
+            assert_eq!(codec.decode_eof(&mut buf).unwrap(), None);
+        }
+
+        #[test]
+        fn max_length_exceeded() {
+            let mut codec = LinesCodec::new_with_max_length(5);
+            let mut buf = BytesMut::from(&b"Hello\nHello,"[..]);
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "Hello");
+            match codec.decode(&mut buf) {
+                Err(LinesCodecError::MaxLineLengthExceeded) => {}
+                res => panic!("unexpected result {:?}", res),
+            }
+        }
+
+        #[test]
+        fn resume_after_overlong_line() {
+            let mut codec = LinesCodec::new_with_max_length(3);
+            let mut buf = BytesMut::from(
+                &b"toolong
+ok
+"[..],
+            );
+            match codec.decode(&mut buf) {
+                Err(LinesCodecError::MaxLineLengthExceeded) => {}
+                res => panic!("unexpected result {:?}", res),
+            }
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "ok");
+
+            let mut buf = BytesMut::from(&b"toolong"[..]);
+            match codec.decode(&mut buf) {
+                Err(LinesCodecError::MaxLineLengthExceeded) => {}
+                res => panic!("unexpected result {:?}", res),
+            }
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+            buf.extend_from_slice(
+                b"line
+ok
+",
+            );
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "ok");
+            assert!(buf.is_empty());
+        }
+
+        #[test]
+        fn max_length_does_not_count_stripped_cr() {
+            let mut codec = LinesCodec::new_with_max_length(5);
+            let mut buf = BytesMut::from(&b"Hello\r"[..]);
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+            buf.extend_from_slice(b"\nHello!\r\n");
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "Hello");
+            match codec.decode(&mut buf) {
+                Err(LinesCodecError::MaxLineLengthExceeded) => {}
+                res => panic!("unexpected result {:?}", res),
+            }
+
+            let mut codec = LinesCodec::new_with_max_length(5).strip_cr(false);
+            let mut buf = BytesMut::from(&b"Hello\r\n"[..]);
+            match codec.decode(&mut buf) {
+                Err(LinesCodecError::MaxLineLengthExceeded) => {}
+                res => panic!("unexpected result {:?}", res),
+            }
+        }
+
+        #[test]
+        fn discard_overlong_with_cr() {
+            let mut codec =
+                LinesCodec::new_with_max_length(5).discard_overlong(true);
+            let mut buf = BytesMut::from(&b"Hello!\nHi\r\n"[..]);
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "Hi");
+            assert!(buf.is_empty());
+        }
+
+        #[test]
+        fn discard_overlong() {
+            let mut codec =
+                LinesCodec::new_with_max_length(5).discard_overlong(true);
+            let mut buf = BytesMut::from(&b"Hello, "[..]);
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+            buf.extend_from_slice(b"world!\nHi\nHello, world!");
+            assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "Hi");
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+            assert_eq!(codec.decode_eof(&mut buf).unwrap(), None);
+        }
+
+        #[test]
+        fn encode() {
+            let mut codec = LinesCodec::new();
+            let mut buf = BytesMut::new();
+            codec.encode(StrChunk::from("Hello"), &mut buf).unwrap();
+            codec.encode("world", &mut buf).unwrap();
+            assert_eq!(buf, &b"Hello\nworld\n"[..]);
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{ExtractUtf8Error, StrChunk};
+
+use bytes::{Buf, BytesMut};
+
+use std::str;
+
+/// A stateful decoder of UTF-8 content in a byte buffer.
+///
+/// `Utf8Decoder` extracts UTF-8 content in the same way as
+/// `StrChunk::extract_utf8`, but it keeps track of how far the input
+/// buffer has been validated. An incomplete UTF-8 sequence left at the
+/// end of the buffer is remembered by the decoder, so that when more
+/// input is appended to the buffer, validation resumes with the
+/// new bytes. Each byte of the input is examined only once, which keeps
+/// decoding linear regardless of how the input is fragmented.
+/// The decoder also counts the consumed input bytes, to report
+/// the stream offsets of invalid sequences.
+///
+/// The decoder should be used with a single buffer, which is only
+/// appended to between calls to the decoding methods. The decoder
+/// state needs to be reset with the `reset` method if the buffer
+/// content is otherwise modified.
+///
+/// # Example
+///
+/// ```rust
+/// # use bytes::BytesMut;
+/// # use strchunk::Utf8Decoder;
+/// let mut decoder = Utf8Decoder::new();
+/// let mut buf = BytesMut::from(&b"\xd0\x97\xd0\xb4\xd1\x80\xd0\xb0\xd0"[..]);
+///
+/// let chunk = decoder.decode(&mut buf).unwrap();
+/// assert_eq!(chunk, "Здра");
+/// assert_eq!(decoder.pending_len(), 1);
+///
+/// buf.extend_from_slice(b"\xb2\xd1\x81\xd1\x82\xd0\xb2\xd1\x83\xd0\xb9");
+/// let chunk = decoder.decode_eof(&mut buf).unwrap();
+/// assert_eq!(chunk, "вствуй");
+/// assert!(buf.is_empty());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Utf8Decoder {
+    // The length of the buffer prefix that has been validated.
+    valid_len: usize,
+    // The incomplete sequence that follows the validated prefix.
+    partial: Partial,
+    // The number of bytes consumed from the stream.
+    position: u64,
+}
+
+// The state of an incomplete UTF-8 sequence: the number of bytes
+// seen so far, the number of bytes still needed, and the range of
+// valid values for the next byte.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+struct Partial {
+    len: u8,
+    need: u8,
+    lower: u8,
+    upper: u8,
+}
+
+impl Partial {
+    // Derives the state from a valid, but incomplete, UTF-8 sequence,
+    // as reported by `str::from_utf8`.
+    fn from_incomplete(bytes: &[u8]) -> Self {
+        debug_assert!(!bytes.is_empty() && bytes.len() < 4);
+        let (seq_len, lower, upper) = match bytes[0] {
+            0xC2..=0xDF => (2, 0x80, 0xBF),
+            0xE0 => (3, 0xA0, 0xBF),
+            0xE1..=0xEC | 0xEE..=0xEF => (3, 0x80, 0xBF),
+            0xED => (3, 0x80, 0x9F),
+            0xF0 => (4, 0x90, 0xBF),
+            0xF1..=0xF3 => (4, 0x80, 0xBF),
+            0xF4 => (4, 0x80, 0x8F),
+            _ => unreachable!("not an incomplete UTF-8 sequence"),
+        };
+        let len = bytes.len() as u8;
+        let (lower, upper) = if len == 1 {
+            (lower, upper)
+        } else {
+            // The constraints only apply to the second byte
+            (0x80, 0xBF)
+        };
+        Partial {
+            len,
+            need: seq_len - len,
+            lower,
+            upper,
+        }
+    }
+}
+
+impl Utf8Decoder {
+    /// Creates a new decoder in the initial state.
+    #[inline]
+    pub fn new() -> Self {
+        Utf8Decoder::default()
+    }
+
+    /// Returns the length of the incomplete UTF-8 sequence found
+    /// at the end of the buffer during the last decoding.
+    #[inline]
+    pub fn pending_len(&self) -> usize {
+        self.partial.len as usize
+    }
+
+    /// Resets the validation state of the decoder.
+    ///
+    /// The stream position is retained.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.valid_len = 0;
+        self.partial = Partial::default();
+    }
+
+    /// Returns the number of input bytes consumed by the decoder so far,
+    /// including the extracted content and the skipped invalid sequences.
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Extracts UTF-8 content from a byte buffer, resuming
+    /// validation where the previous call left off.
+    ///
+    /// The content of `src` that validates as UTF-8 is split off
+    /// and returned, up to a possibly incomplete UTF-8 sequence at the end,
+    /// which is left in `src`.
+    ///
+    /// # Errors
+    ///
+    /// If an invalid UTF-8 sequence is encountered within `src`, an error
+    /// value with recovery information is returned in the `Err` variant.
+    /// The valid UTF-8 part preceding the invalid sequence is taken
+    /// out of `src` and can be obtained from the `ExtractUtf8Error` value.
+    /// Unlike `StrChunk::extract_utf8`, the decoder also removes the
+    /// invalid sequence from `src`, so that decoding can be resumed
+    /// with the next call without the caller having to adjust the buffer
+    /// in step with the decoder state. The length and the bytes of
+    /// the removed sequence, as well as its offset in the stream,
+    /// are provided by the error value.
+    pub fn decode(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<StrChunk, ExtractUtf8Error> {
+        let res = self.validate(src);
+        let bytes = self.split_valid(src).freeze();
+        // Safety: the extracted content has been validated
+        let extracted = unsafe { StrChunk::from_utf8_unchecked(bytes) };
+        match res {
+            Ok(_) => Ok(extracted),
+            Err(error_len) => Err(self.skip_invalid(extracted, src, error_len)),
+        }
+    }
+
+    /// Extracts UTF-8 content from a byte buffer at the end of input.
+    ///
+    /// This method works like `decode`, except that an incomplete
+    /// UTF-8 sequence at the end of `src` is also treated as invalid.
+    ///
+    /// # Errors
+    ///
+    /// If an invalid or incomplete UTF-8 sequence is encountered within
+    /// `src`, an error value is returned in the same way as described
+    /// for `decode`.
+    pub fn decode_eof(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<StrChunk, ExtractUtf8Error> {
+        let extracted = self.decode(src)?;
+        if src.is_empty() {
+            Ok(extracted)
+        } else {
+            self.partial = Partial::default();
+            let error_len = src.len();
+            Err(self.skip_invalid(extracted, src, error_len))
+        }
+    }
+
+    // Splits off the validated prefix of `src`, counting it as consumed.
+    pub(crate) fn split_valid(&mut self, src: &mut BytesMut) -> BytesMut {
+        let bytes = src.split_to(self.valid_len);
+        self.position += self.valid_len as u64;
+        self.valid_len = 0;
+        bytes
+    }
+
+    // Removes an invalid sequence from the start of `src`, returning
+    // the error value to report it.
+    pub(crate) fn skip_invalid(
+        &mut self,
+        extracted: StrChunk,
+        src: &mut BytesMut,
+        error_len: usize,
+    ) -> ExtractUtf8Error {
+        let err = ExtractUtf8Error::new(extracted, &src[..error_len])
+            .with_stream_offset(self.position);
+        src.advance(error_len);
+        self.position += error_len as u64;
+        err
+    }
+
+    // Returns the length of the validated prefix of the buffer.
+    #[inline]
+    pub(crate) fn valid_len(&self) -> usize {
+        self.valid_len
+    }
+
+    // Resets the validation state, with the first `valid_len` bytes
+    // of the buffer known to be valid UTF-8.
+    #[inline]
+    pub(crate) fn reset_to(&mut self, valid_len: usize) {
+        self.valid_len = valid_len;
+        self.partial = Partial::default();
+    }
+
+    /// Validates the content of `src` past the previously validated prefix.
+    ///
+    /// Returns the length of the valid UTF-8 prefix of `src`, not including
+    /// an incomplete sequence at the end. If an invalid sequence is
+    /// encountered, the length of the invalid sequence is returned in
+    /// the `Err` variant and the valid prefix length is updated to
+    /// the start of the invalid sequence.
+    pub(crate) fn validate(&mut self, src: &[u8]) -> Result<usize, usize> {
+        debug_assert!(src.len() >= self.valid_len + self.pending_len());
+        let mut pos = self.valid_len + self.pending_len();
+        while self.partial.len != 0 {
+            let b = match src.get(pos) {
+                None => return Ok(self.valid_len),
+                Some(&b) => b,
+            };
+            let partial = &mut self.partial;
+            if b < partial.lower || b > partial.upper {
+                let error_len = partial.len as usize;
+                *partial = Partial::default();
+                return Err(error_len);
+            }
+            pos += 1;
+            partial.need -= 1;
+            if partial.need == 0 {
+                self.valid_len = pos;
+                *partial = Partial::default();
+            } else {
+                partial.len += 1;
+                partial.lower = 0x80;
+                partial.upper = 0xBF;
+            }
+        }
+        match str::from_utf8(&src[pos..]) {
+            Ok(_) => {
+                self.valid_len = src.len();
+                Ok(self.valid_len)
+            }
+            Err(e) => {
+                self.valid_len = pos + e.valid_up_to();
+                match e.error_len() {
+                    None => {
+                        self.partial =
+                            Partial::from_incomplete(&src[self.valid_len..]);
+                        Ok(self.valid_len)
+                    }
+                    Some(error_len) => Err(error_len),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_by_byte(input: &[u8]) -> (String, Vec<usize>) {
+        let mut decoder = Utf8Decoder::new();
+        let mut buf = BytesMut::new();
+        let mut text = String::new();
+        let mut error_lens = Vec::new();
+        for b in input {
+            buf.extend_from_slice(&[*b]);
+            loop {
+                match decoder.decode(&mut buf) {
+                    Ok(chunk) => {
+                        text.push_str(&chunk);
+                        break;
+                    }
+                    Err(e) => {
+                        error_lens.push(e.error_len());
+                        text.push_str(&e.into_extracted());
+                        text.push('\u{FFFD}');
+                    }
+                }
+            }
+        }
+        (text, error_lens)
+    }
+
+    #[test]
+    fn decode_fragmented() {
+        let input = "Hello, Привет, 你好, 🦀!";
+        let (text, error_lens) = decode_by_byte(input.as_bytes());
+        assert_eq!(text, input);
+        assert!(error_lens.is_empty());
+    }
+
+    #[test]
+    fn decode_fragmented_invalid() {
+        const TEST_DATA: &[u8] = b"\x61\xF1\x80\x80\xE1\x80\xC2\x62\x80\x63\x80\xBF\x64\xED\xA0\x80\xF4\x90";
+        let (text, _) = decode_by_byte(TEST_DATA);
+        assert_eq!(text, String::from_utf8_lossy(TEST_DATA));
+    }
+
+    #[test]
+    fn error_len_matches_std() {
+        for input in [
+            &b"\xE1\x80\x41"[..],
+            b"\xF0\x90\x80\x41",
+            b"\xE0\x80",
+            b"\xF4\x90",
+            b"\xED\xA0",
+            b"\xC0\x80",
+        ] {
+            let expected = str::from_utf8(input).unwrap_err().error_len();
+            let (_, error_lens) = decode_by_byte(input);
+            assert_eq!(error_lens.first().copied(), expected, "{:x?}", input);
+        }
+    }
+
+    #[test]
+    fn decode_eof_with_incomplete_sequence() {
+        let mut decoder = Utf8Decoder::new();
+        let mut buf = BytesMut::from(&b"Hello\xF0\x90"[..]);
+        let err = decoder.decode_eof(&mut buf).unwrap_err();
+        assert_eq!(err.error_len(), 2);
+        assert_eq!(err.invalid_bytes(), b"\xF0\x90");
+        assert_eq!(err.into_extracted(), "Hello");
+        assert_eq!(decoder.pending_len(), 0);
+        assert_eq!(decoder.position(), 7);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn stream_offset() {
+        let mut decoder = Utf8Decoder::new();
+        let mut buf = BytesMut::from(&b"Hello, "[..]);
+        decoder.decode(&mut buf).unwrap();
+        buf.extend_from_slice(b"w\xF0\x90\x80orld\xFF");
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert_eq!(err.valid_up_to(), 1);
+        assert_eq!(err.stream_offset(), Some(8));
+        assert_eq!(
+            err.to_string(),
+            "invalid UTF-8 sequence \\xF0\\x90\\x80 at offset 1 \
+             (stream offset 8)"
+        );
+        assert_eq!(buf, &b"orld\xFF"[..]);
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert_eq!(err.into_extracted(), "orld");
+        assert_eq!(decoder.position(), 16);
+    }
+}
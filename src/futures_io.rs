@@ -0,0 +1,267 @@
+//! Adapters for asynchronous I/O with `futures::io`.
+//!
+//! This module is available with the `futures-io` feature enabled.
+//! The adapters decode input in the same way as their counterparts
+//! in the `tokio` module, making them usable with runtimes built
+//! on the `futures` I/O traits.
+
+use crate::reader::{async_utf8_reader, ReadBuffer};
+use crate::StrChunk;
+
+use bytes::BytesMut;
+use futures_io::{AsyncBufRead, AsyncRead};
+
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+async_utf8_reader! {
+    /// Decodes UTF-8 text read from an `AsyncRead` source.
+    ///
+    /// `Utf8Reader` reads input into an internal buffer and extracts
+    /// the complete UTF-8 content from it as `StrChunk` values without
+    /// copying, using a `Utf8Decoder`. A UTF-8 sequence split between
+    /// reads is carried over to be completed by the next read, and
+    /// the bytes preceding it are not validated again.
+    ///
+    /// The reader also implements `Stream`, producing the decoded chunks
+    /// until the end of input is reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use strchunk::futures_io::Utf8Reader;
+    ///
+    /// let input: &[u8] = "Привет".as_bytes();
+    /// let mut reader = Utf8Reader::new(input);
+    /// let chunk = reader.read_utf8().await?;
+    /// assert_eq!(chunk, "Привет");
+    /// let chunk = reader.read_utf8().await?;
+    /// assert!(chunk.is_empty());
+    /// # std::io::Result::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub struct Utf8Reader<R: AsyncRead> {
+        poll_decode: poll_decode_read,
+    }
+}
+
+async_utf8_reader! {
+    /// Decodes UTF-8 text read from an `AsyncBufRead` source.
+    ///
+    /// `Utf8BufReader` works like `Utf8Reader`, but takes the input
+    /// from the buffer of the source with `poll_fill_buf`. The bytes
+    /// available in the source buffer are copied directly into
+    /// the decoding buffer, which does not need to be initialized
+    /// for a read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use futures::io::BufReader;
+    /// use futures::stream::TryStreamExt;
+    /// use strchunk::futures_io::Utf8BufReader;
+    /// use strchunk::StrChunk;
+    ///
+    /// let input = BufReader::new("Привет, мир!".as_bytes());
+    /// let reader = Utf8BufReader::new(input);
+    /// let chunks: Vec<StrChunk> = reader.try_collect().await?;
+    /// assert_eq!(chunks.concat(), "Привет, мир!");
+    /// # std::io::Result::Ok(())
+    /// # }).unwrap();
+    /// ```
+    pub struct Utf8BufReader<R: AsyncBufRead> {
+        poll_decode: poll_decode_buf_read,
+    }
+}
+
+fn poll_decode_read<R: AsyncRead>(
+    mut reader: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuffer,
+) -> Poll<io::Result<StrChunk>> {
+    // AsyncRead implementations require an initialized buffer
+    buf.poll_decode_initialized(|dst| reader.as_mut().poll_read(cx, dst))
+}
+
+fn poll_decode_buf_read<R: AsyncBufRead>(
+    mut reader: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuffer,
+) -> Poll<io::Result<StrChunk>> {
+    buf.poll_decode(|dst| poll_fill_buf(reader.as_mut(), cx, dst))
+}
+
+fn poll_fill_buf<R: AsyncBufRead>(
+    mut reader: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    buf: &mut BytesMut,
+) -> Poll<io::Result<usize>> {
+    let available = ready!(reader.as_mut().poll_fill_buf(cx))?;
+    let len = available.len().min(buf.capacity() - buf.len());
+    buf.extend_from_slice(&available[..len]);
+    reader.consume(len);
+    Poll::Ready(Ok(len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExtractUtf8Error;
+
+    use futures::executor::block_on;
+    use futures::io::BufReader;
+    use futures::stream::TryStreamExt;
+
+    // Returns the input in pieces of the given size.
+    struct Fragmented {
+        data: &'static [u8],
+        piece_len: usize,
+    }
+
+    impl AsyncRead for Fragmented {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            let len = self.piece_len.min(self.data.len()).min(buf.len());
+            let (piece, rest) = self.data.split_at(len);
+            buf[..len].copy_from_slice(piece);
+            self.data = rest;
+            Poll::Ready(Ok(len))
+        }
+    }
+
+    #[test]
+    fn read_split_sequences() {
+        let input = Fragmented {
+            data: "Привет".as_bytes(),
+            piece_len: 3,
+        };
+        let reader = Utf8Reader::new(input);
+        let chunks: Vec<StrChunk> = block_on(reader.try_collect()).unwrap();
+        assert_eq!(chunks.concat(), "Привет");
+    }
+
+    enum Step {
+        Pending,
+        Interrupted,
+        Byte,
+    }
+
+    // Follows a script of poll results, filling the whole buffer
+    // on each poll.
+    struct Scripted {
+        steps: std::vec::IntoIter<Step>,
+        saw_scribbles: Vec<bool>,
+    }
+
+    impl AsyncRead for Scripted {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            self.saw_scribbles.push(buf.iter().all(|&b| b == 0xFF));
+            buf.fill(0xFF);
+            match self.steps.next() {
+                None => Poll::Ready(Ok(0)),
+                Some(Step::Pending) => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+                Some(Step::Interrupted) => {
+                    Poll::Ready(Err(io::ErrorKind::Interrupted.into()))
+                }
+                Some(Step::Byte) => {
+                    buf[0] = b'a';
+                    Poll::Ready(Ok(1))
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn interrupted_is_passed_through() {
+        use Step::*;
+        let input = Scripted {
+            steps: vec![Pending, Byte, Interrupted, Byte, Pending, Byte]
+                .into_iter(),
+            saw_scribbles: Vec::new(),
+        };
+        let mut reader = Utf8Reader::with_capacity(16, input);
+        let mut text = String::new();
+        let mut interrupted = 0;
+        block_on(async {
+            loop {
+                match reader.read_utf8().await {
+                    Ok(chunk) if chunk.is_empty() => break,
+                    Ok(chunk) => text.push_str(&chunk),
+                    Err(e) => {
+                        assert_eq!(e.kind(), io::ErrorKind::Interrupted);
+                        interrupted += 1;
+                    }
+                }
+            }
+        });
+        assert_eq!(text, "aaa");
+        assert_eq!(interrupted, 1);
+        // The spare capacity is only zeroed before the first poll
+        let saw_scribbles = &reader.get_ref().saw_scribbles;
+        assert_eq!(saw_scribbles.len(), 7);
+        assert!(!saw_scribbles[0]);
+        assert!(saw_scribbles[1..].iter().all(|&seen| seen));
+    }
+
+    #[test]
+    fn read_with_small_capacity() {
+        let input: &[u8] = "Привет, мир!".as_bytes();
+        let reader = Utf8Reader::with_capacity(5, input);
+        let chunks: Vec<StrChunk> = block_on(reader.try_collect()).unwrap();
+        assert_eq!(chunks.concat(), "Привет, мир!");
+    }
+
+    #[test]
+    fn buf_read_split_sequences() {
+        let input = Fragmented {
+            data: "Привет, мир!".as_bytes(),
+            piece_len: 3,
+        };
+        let input = BufReader::with_capacity(5, input);
+        let reader = Utf8BufReader::with_capacity(4, input);
+        let chunks: Vec<StrChunk> = block_on(reader.try_collect()).unwrap();
+        assert_eq!(chunks.concat(), "Привет, мир!");
+    }
+
+    #[test]
+    fn incomplete_sequence_at_eof() {
+        block_on(async {
+            let input: &[u8] = b"Hello\xD0";
+            let mut reader = Utf8BufReader::new(input);
+            assert_eq!(reader.read_utf8().await.unwrap(), "Hello");
+            let err = reader.read_utf8().await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(reader.read_utf8().await.unwrap().is_empty());
+        })
+    }
+
+    #[test]
+    fn resume_after_invalid_sequence() {
+        block_on(async {
+            let input: &[u8] = b"Hello \xF0\x90\x80World";
+            let mut reader = Utf8Reader::new(input);
+            let err = reader.read_utf8().await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            let err = err
+                .into_inner()
+                .unwrap()
+                .downcast::<ExtractUtf8Error>()
+                .unwrap();
+            assert_eq!(err.into_extracted(), "Hello ");
+            assert_eq!(reader.read_utf8().await.unwrap(), "World");
+        })
+    }
+}
@@ -4,7 +4,9 @@ use crate::{StrChunk, StrChunkMut};
 
 use range_split::TakeRange;
 
-use std::ops::{RangeFrom, RangeFull, RangeTo, RangeToInclusive};
+use std::ops::{
+    Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
 
 // A generic impl implemented through the intrinsic take_range/remove_range
 // would be enough for the purposes of this crate, but it would commit to
@@ -36,6 +38,31 @@ impl_take_range!(<RangeFrom<usize>> for StrChunkMut);
 impl_take_range!(<RangeTo<usize>> for StrChunkMut);
 impl_take_range!(<RangeToInclusive<usize>> for StrChunkMut);
 
+// Ranges bounded at both ends may leave parts of the string on both
+// sides of the range. These are spliced together to form the remainder,
+// which has to be copied unless one of the parts is empty.
+// The parts can be obtained separately with the `split_range` methods.
+macro_rules! impl_take_bounded_range {
+    (<$Range:ty> for $T:path) => {
+        impl TakeRange<$Range> for $T {
+            type Output = $T;
+
+            fn take_range(&mut self, range: $Range) -> Self::Output {
+                Self::take_bounded_range(self, range)
+            }
+
+            fn remove_range(&mut self, range: $Range) {
+                Self::remove_bounded_range(self, range)
+            }
+        }
+    };
+}
+
+impl_take_bounded_range!(<Range<usize>> for StrChunk);
+impl_take_bounded_range!(<RangeInclusive<usize>> for StrChunk);
+impl_take_bounded_range!(<Range<usize>> for StrChunkMut);
+impl_take_bounded_range!(<RangeInclusive<usize>> for StrChunkMut);
+
 #[cfg(feature = "specialization")]
 mod generic {
     use crate::{StrChunk, StrChunkMut};
@@ -146,6 +173,7 @@ mod tedious {
         {
             impl <$Rhs:ty> for $T:ty
         } => {
+            #[allow(clippy::non_canonical_partial_ord_impl)]
             impl PartialOrd<$Rhs> for $T {
                 #[inline]
                 fn partial_cmp(&self, other: &$Rhs) -> Option<Ordering> {
@@ -289,6 +317,48 @@ mod tests {
                     let mut buf = "Привет".into();
                     $func(&mut buf, ..=5, "При", "вет");
                 }
+
+                #[test]
+                fn bounded_full() {
+                    let mut buf = "Hello".into();
+                    $func(&mut buf, 0..5, "Hello", "");
+                }
+
+                #[test]
+                fn bounded_empty() {
+                    let mut buf = "Привет".into();
+                    $func(&mut buf, 6..6, "", "Привет");
+                }
+
+                #[test]
+                fn bounded_head() {
+                    let mut buf = "Привет".into();
+                    $func(&mut buf, 0..4, "Пр", "ивет");
+                }
+
+                #[test]
+                fn bounded_tail() {
+                    let mut buf = "Привет".into();
+                    $func(&mut buf, 8..12, "ет", "Прив");
+                }
+
+                #[test]
+                fn bounded_mid() {
+                    let mut buf = "Привет".into();
+                    $func(&mut buf, 2..8, "рив", "Пет");
+                }
+
+                #[test]
+                fn inclusive_full() {
+                    let mut buf = "Hello".into();
+                    $func(&mut buf, 0..=4, "Hello", "");
+                }
+
+                #[test]
+                fn inclusive_mid() {
+                    let mut buf = "Привет".into();
+                    $func(&mut buf, 2..=7, "рив", "Пет");
+                }
             };
         }
 
@@ -335,6 +405,49 @@ mod tests {
                     let mut buf = "Привет".into();
                     $func(&mut buf, ..=2);
                 }
+
+                #[test]
+                #[should_panic]
+                fn panics_on_oob_bounded() {
+                    let mut buf = "Hello".into();
+                    $func(&mut buf, 2..6);
+                }
+
+                #[test]
+                #[should_panic]
+                fn panics_on_oob_inclusive() {
+                    let mut buf = "Hello".into();
+                    $func(&mut buf, 2..=5);
+                }
+
+                #[test]
+                #[should_panic]
+                fn panics_on_reversed_bounded() {
+                    let mut buf = "Hello".into();
+                    #[allow(clippy::reversed_empty_ranges)]
+                    $func(&mut buf, 3..2);
+                }
+
+                #[test]
+                #[should_panic]
+                fn panics_on_split_utf8_bounded_start() {
+                    let mut buf = "Привет".into();
+                    $func(&mut buf, 3..8);
+                }
+
+                #[test]
+                #[should_panic]
+                fn panics_on_split_utf8_bounded_end() {
+                    let mut buf = "Привет".into();
+                    $func(&mut buf, 2..7);
+                }
+
+                #[test]
+                #[should_panic]
+                fn panics_on_split_utf8_inclusive() {
+                    let mut buf = "Привет".into();
+                    $func(&mut buf, 2..=6);
+                }
             };
         }
 
@@ -397,6 +510,40 @@ mod tests {
                         TakeRange::remove_range(buf, range);
                     });
                 }
+
+                mod split_range {
+                    use super::*;
+
+                    test_take_range_effects_with!(
+                        |buf: &mut $T,
+                         range,
+                         expected_output,
+                         expected_remainder| {
+                            let method_dbg =
+                                format!("split_range({:?})", &range);
+                            let (head, mid, tail) =
+                                std::mem::take(buf).split_range(range);
+                            assert_eq!(
+                                mid,
+                                expected_output,
+                                "expected output of `{}` for `{}`",
+                                method_dbg,
+                                stringify!($T)
+                            );
+                            assert_eq!(
+                                format!("{}{}", head, tail),
+                                expected_remainder,
+                                "expected outer parts of `{}` for `{}`",
+                                method_dbg,
+                                stringify!($T)
+                            );
+                        }
+                    );
+
+                    test_take_range_panics_with!(|buf: &mut $T, range| {
+                        std::mem::take(buf).split_range(range)
+                    });
+                }
             };
         }
 
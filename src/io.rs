@@ -0,0 +1,425 @@
+//! Adapters for synchronous I/O with `std::io`.
+
+use crate::buf::Utf8BufMut;
+use crate::reader::{
+    incomplete_utf8_error, ReadBuffer, DEFAULT_BUFFER_CAPACITY,
+};
+use crate::{StrChunk, StrChunkMut};
+
+use bytes::BufMut;
+
+use std::io::{self, Read, Write};
+use std::task::Poll;
+
+/// Decodes UTF-8 text read from a blocking `Read` source.
+///
+/// `Utf8Reader` reads input into an internal buffer and extracts
+/// the complete UTF-8 content from it as `StrChunk` values without
+/// copying, using a `Utf8Decoder`. A UTF-8 sequence split between
+/// reads is carried over to be completed by the next read, and
+/// the bytes preceding it are not validated again.
+///
+/// The reader also implements `Iterator`, producing the decoded chunks
+/// until the end of input is reached.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> std::io::Result<()> {
+/// use strchunk::io::Utf8Reader;
+///
+/// let input: &[u8] = "Привет".as_bytes();
+/// let mut reader = Utf8Reader::new(input);
+/// let chunk = reader.read_utf8()?;
+/// assert_eq!(chunk, "Привет");
+/// let chunk = reader.read_utf8()?;
+/// assert!(chunk.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Utf8Reader<R> {
+    inner: R,
+    buf: ReadBuffer,
+}
+
+impl<R> Utf8Reader<R> {
+    /// Creates a new `Utf8Reader` with the default buffer capacity.
+    ///
+    /// The default capacity is currently 8 KiB, but this may change
+    /// in the future.
+    pub fn new(inner: R) -> Self {
+        Utf8Reader::with_capacity(DEFAULT_BUFFER_CAPACITY, inner)
+    }
+
+    /// Creates a new `Utf8Reader` with the specified buffer capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is less than 4, which is not enough to hold
+    /// every possible UTF-8 sequence.
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        Utf8Reader {
+            inner,
+            buf: ReadBuffer::with_capacity(capacity),
+        }
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from the underlying reader would corrupt
+    /// the decoded text.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Consumes the `Utf8Reader`, returning the underlying reader.
+    ///
+    /// Any input buffered but not yet decoded is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Utf8Reader<R> {
+    /// Reads and decodes UTF-8 content from the underlying reader.
+    ///
+    /// On success, returns a non-empty chunk of text, or an empty chunk
+    /// if the end of input has been reached.
+    ///
+    /// # Errors
+    ///
+    /// I/O errors of the underlying reader are passed through, except
+    /// for errors of kind `Interrupted`, on which the read is retried.
+    /// An invalid UTF-8 sequence in the input results in an error
+    /// of kind `InvalidData` wrapping an `ExtractUtf8Error` that holds
+    /// the text decoded before the invalid sequence; the invalid
+    /// sequence is skipped so that reading can be resumed.
+    /// An incomplete UTF-8 sequence at the end of input is also
+    /// reported as an `InvalidData` error.
+    pub fn read_utf8(&mut self) -> io::Result<StrChunk> {
+        let inner = &mut self.inner;
+        match self.buf.poll_decode_initialized(|dst| {
+            Poll::Ready(read_retrying(inner, dst))
+        }) {
+            Poll::Ready(res) => res,
+            Poll::Pending => unreachable!("blocking read returned Pending"),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Utf8Reader<R> {
+    type Item = io::Result<StrChunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_utf8() {
+            Ok(chunk) if chunk.is_empty() => None,
+            res => Some(res),
+        }
+    }
+}
+
+fn read_retrying<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match reader.read(buf) {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            res => return res,
+        }
+    }
+}
+
+/// Collects UTF-8 text written through `std::io::Write`.
+///
+/// `Utf8Writer` validates the bytes as they are written and appends
+/// them to a `StrChunkMut`, which is obtained with `into_inner` when
+/// writing is done. A UTF-8 sequence split between writes is held
+/// pending until the bytes completing it are written.
+///
+/// By default, invalid UTF-8 data is rejected with an error of kind
+/// `InvalidData`. A writer created with `lossy` substitutes each
+/// invalid sequence with the replacement character U+FFFD instead.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> std::io::Result<()> {
+/// use std::io::Write;
+/// use strchunk::io::Utf8Writer;
+///
+/// let mut writer = Utf8Writer::new();
+/// writer.write_all(&"Привет".as_bytes()[..3])?;
+/// assert_eq!(writer.as_str(), "П");
+/// writer.write_all(&"Привет".as_bytes()[3..])?;
+/// write!(writer, ", {}!", "мир")?;
+/// let text = writer.into_inner()?;
+/// assert_eq!(text, "Привет, мир!");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Utf8Writer {
+    buf: Utf8BufMut,
+    lossy: bool,
+}
+
+impl Utf8Writer {
+    /// Creates a new `Utf8Writer` with an empty string.
+    pub fn new() -> Self {
+        Utf8Writer::default()
+    }
+
+    /// Creates a new `Utf8Writer` with an empty string of
+    /// the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Utf8Writer {
+            buf: Utf8BufMut::with_capacity(capacity),
+            lossy: false,
+        }
+    }
+
+    /// Makes the writer substitute invalid UTF-8 sequences with
+    /// the replacement character U+FFFD, rather than fail with an error.
+    ///
+    /// The substitution follows the same rules as
+    /// `String::from_utf8_lossy`.
+    pub fn lossy(mut self) -> Self {
+        self.lossy = true;
+        self
+    }
+
+    /// Returns the text written so far, not including an incomplete
+    /// UTF-8 sequence at the end.
+    pub fn as_str(&self) -> &str {
+        self.buf.as_str()
+    }
+
+    /// Consumes the `Utf8Writer`, returning the written text.
+    ///
+    /// # Errors
+    ///
+    /// If the written data ends with an incomplete UTF-8 sequence,
+    /// an error of kind `InvalidData` is returned. A lossy writer
+    /// substitutes the incomplete sequence with the replacement character.
+    pub fn into_inner(mut self) -> io::Result<StrChunkMut> {
+        if !self.buf.pending_bytes().is_empty() {
+            if !self.lossy {
+                return Err(incomplete_utf8_error());
+            }
+            self.buf.discard_pending();
+            self.buf.put_slice("\u{FFFD}".as_bytes());
+        }
+        Ok(self.buf.split_text().unwrap())
+    }
+}
+
+impl Write for Utf8Writer {
+    /// Validates and appends the bytes of `data` to the text.
+    ///
+    /// # Errors
+    ///
+    /// Unless the writer is lossy, an invalid UTF-8 sequence results in
+    /// a short write up to the start of the sequence. If the invalid
+    /// sequence is at the start of `data`, or begins with an incomplete
+    /// sequence left by previous writes, an error of kind `InvalidData`
+    /// is returned; in the latter case, the incomplete sequence
+    /// is discarded.
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let start = self.buf.as_str().len() + self.buf.pending_bytes().len();
+        self.buf.put_slice(data);
+        if self.lossy {
+            self.buf.replace_invalid();
+            return Ok(data.len());
+        }
+        if self.buf.invalid_len().is_none() {
+            return Ok(data.len());
+        }
+        let valid_len = self.buf.as_str().len();
+        self.buf.discard_pending();
+        if valid_len > start {
+            Ok(valid_len - start)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid UTF-8 sequence in input",
+            ))
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExtractUtf8Error;
+
+    // Returns the input in pieces of the given size.
+    struct Fragmented {
+        data: &'static [u8],
+        piece_len: usize,
+    }
+
+    impl Read for Fragmented {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.piece_len.min(self.data.len()).min(buf.len());
+            let (piece, rest) = self.data.split_at(len);
+            buf[..len].copy_from_slice(piece);
+            self.data = rest;
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn read_split_sequences() {
+        let input = Fragmented {
+            data: "Привет".as_bytes(),
+            piece_len: 3,
+        };
+        let reader = Utf8Reader::new(input);
+        let chunks = reader.collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(chunks.concat(), "Привет");
+    }
+
+    // Records the sizes of the buffers passed to it.
+    struct Recording {
+        inner: Fragmented,
+        buf_sizes: Vec<usize>,
+    }
+
+    impl Read for Recording {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.buf_sizes.push(buf.len());
+            self.inner.read(buf)
+        }
+    }
+
+    #[test]
+    fn reads_are_not_starved_after_partial_reads() {
+        let input = Recording {
+            inner: Fragmented {
+                data: b"Hello, world!",
+                piece_len: 3,
+            },
+            buf_sizes: Vec::new(),
+        };
+        let mut reader = Utf8Reader::with_capacity(8, input);
+        let chunks = reader.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(chunks.concat(), "Hello, world!");
+        let buf_sizes = &reader.get_ref().buf_sizes;
+        assert!(buf_sizes.iter().all(|&len| len >= 4), "{:?}", buf_sizes);
+    }
+
+    // Fills the whole buffer on each read, but reports a single byte.
+    struct Scribbling {
+        reads_left: usize,
+        saw_scribbles: Vec<bool>,
+    }
+
+    impl Read for Scribbling {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.reads_left == 0 {
+                return Ok(0);
+            }
+            self.reads_left -= 1;
+            self.saw_scribbles.push(buf.iter().all(|&b| b == 0xFF));
+            buf.fill(0xFF);
+            buf[0] = b'a';
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn spare_capacity_is_not_zeroed_again() {
+        let input = Scribbling {
+            reads_left: 4,
+            saw_scribbles: Vec::new(),
+        };
+        let mut reader = Utf8Reader::with_capacity(16, input);
+        let chunks = reader.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(chunks.concat(), "aaaa");
+        assert_eq!(reader.get_ref().saw_scribbles, [false, true, true, true]);
+    }
+
+    #[test]
+    fn read_with_small_capacity() {
+        let input: &[u8] = "Привет, мир!".as_bytes();
+        let reader = Utf8Reader::with_capacity(5, input);
+        let chunks = reader.collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(chunks.concat(), "Привет, мир!");
+    }
+
+    #[test]
+    fn incomplete_sequence_at_eof() {
+        let input: &[u8] = b"Hello\xD0";
+        let mut reader = Utf8Reader::new(input);
+        assert_eq!(reader.read_utf8().unwrap(), "Hello");
+        let err = reader.read_utf8().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(reader.read_utf8().unwrap().is_empty());
+    }
+
+    #[test]
+    fn resume_after_invalid_sequence() {
+        let input: &[u8] = b"Hello \xF0\x90\x80World";
+        let mut reader = Utf8Reader::new(input);
+        let err = reader.read_utf8().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = err
+            .into_inner()
+            .unwrap()
+            .downcast::<ExtractUtf8Error>()
+            .unwrap();
+        assert_eq!(err.into_extracted(), "Hello ");
+        assert_eq!(reader.read_utf8().unwrap(), "World");
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn write_split_sequences() {
+        let mut writer = Utf8Writer::new();
+        for b in "Привет, 🦀!".as_bytes() {
+            writer.write_all(&[*b]).unwrap();
+        }
+        assert_eq!(writer.into_inner().unwrap(), "Привет, 🦀!");
+    }
+
+    #[test]
+    fn write_invalid_data() {
+        let mut writer = Utf8Writer::new();
+        assert_eq!(writer.write(b"Hello \xF0\x90\x80World").unwrap(), 6);
+        let err = writer.write(b"\xF0\x90\x80World").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        writer.write_all(b"\xE2\x82").unwrap();
+        let err = writer.write(b"World").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        writer.write_all(b"World").unwrap();
+        assert_eq!(writer.into_inner().unwrap(), "Hello World");
+    }
+
+    #[test]
+    fn write_lossy() {
+        const TEST_DATA: &[u8] = b"\x61\xF1\x80\x80\xE1\x80\xC2\x62\x80\x63\x80\xBF\x64\xED\xA0\x80\xF4\x90";
+        let mut writer = Utf8Writer::new().lossy();
+        for piece in TEST_DATA.chunks(3) {
+            writer.write_all(piece).unwrap();
+        }
+        let text = writer.into_inner().unwrap();
+        assert_eq!(text, String::from_utf8_lossy(TEST_DATA));
+    }
+
+    #[test]
+    fn incomplete_sequence_at_end_of_output() {
+        let mut writer = Utf8Writer::new();
+        writer.write_all(b"Hello\xD0").unwrap();
+        assert_eq!(writer.as_str(), "Hello");
+        let err = writer.into_inner().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
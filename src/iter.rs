@@ -0,0 +1,644 @@
+//! Iterators over parts of `StrChunk` and `StrChunkMut`.
+//!
+//! Most iterators in this module are returned by the searching methods
+//! of `StrChunk`. They hold a reference to the buffer of the string
+//! they have been created from, and yield `StrChunk` values sharing
+//! that buffer.
+
+use crate::pattern::Pattern;
+use crate::{StrChunk, StrChunkMut};
+
+use std::iter::FusedIterator;
+
+// Finds successive non-overlapping matches from the front of the string.
+#[derive(Clone, Debug)]
+struct Matcher<P> {
+    chunk: StrChunk,
+    pat: P,
+    pos: usize,
+    done: bool,
+}
+
+impl<P: Pattern> Matcher<P> {
+    fn new(chunk: StrChunk, pat: P) -> Self {
+        Matcher {
+            chunk,
+            pat,
+            pos: 0,
+            done: false,
+        }
+    }
+
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        if self.done {
+            return None;
+        }
+        let s = self.chunk.as_str();
+        let (start, end) = match self.pat.find_in(&s[self.pos..]) {
+            None => {
+                self.done = true;
+                return None;
+            }
+            Some((start, end)) => (self.pos + start, self.pos + end),
+        };
+        if start != end {
+            self.pos = end;
+        } else {
+            // An empty match is found at every character boundary,
+            // so the search needs to step over the next character.
+            match s[end..].chars().next() {
+                Some(c) => self.pos = end + c.len_utf8(),
+                None => self.done = true,
+            }
+        }
+        Some((start, end))
+    }
+}
+
+// Finds successive non-overlapping matches from the back of the string.
+#[derive(Clone, Debug)]
+struct RevMatcher<P> {
+    chunk: StrChunk,
+    pat: P,
+    pos: usize,
+    done: bool,
+}
+
+impl<P: Pattern> RevMatcher<P> {
+    fn new(chunk: StrChunk, pat: P) -> Self {
+        let pos = chunk.len();
+        RevMatcher {
+            chunk,
+            pat,
+            pos,
+            done: false,
+        }
+    }
+
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        if self.done {
+            return None;
+        }
+        let s = self.chunk.as_str();
+        let (start, end) = match self.pat.rfind_in(&s[..self.pos]) {
+            None => {
+                self.done = true;
+                return None;
+            }
+            Some(range) => range,
+        };
+        if start != end {
+            self.pos = start;
+        } else {
+            match s[..start].chars().next_back() {
+                Some(c) => self.pos = start - c.len_utf8(),
+                None => self.done = true,
+            }
+        }
+        Some((start, end))
+    }
+}
+
+/// An iterator over substrings of a `StrChunk`, separated by
+/// a pattern.
+///
+/// This struct is created by the `split_chunks` and
+/// `split_terminator_chunks` methods of `StrChunk`.
+#[derive(Clone, Debug)]
+pub struct SplitChunks<P> {
+    matcher: Matcher<P>,
+    start: usize,
+    allow_trailing_empty: bool,
+    finished: bool,
+}
+
+impl<P: Pattern> SplitChunks<P> {
+    pub(crate) fn new(chunk: StrChunk, pat: P) -> Self {
+        SplitChunks {
+            matcher: Matcher::new(chunk, pat),
+            start: 0,
+            allow_trailing_empty: true,
+            finished: false,
+        }
+    }
+
+    pub(crate) fn terminator(chunk: StrChunk, pat: P) -> Self {
+        SplitChunks {
+            allow_trailing_empty: false,
+            ..SplitChunks::new(chunk, pat)
+        }
+    }
+
+    fn remainder(&mut self) -> Option<StrChunk> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        let chunk = &self.matcher.chunk;
+        if !self.allow_trailing_empty && self.start == chunk.len() {
+            return None;
+        }
+        Some(chunk.slice(self.start..))
+    }
+}
+
+impl<P: Pattern> Iterator for SplitChunks<P> {
+    type Item = StrChunk;
+
+    fn next(&mut self) -> Option<StrChunk> {
+        if self.finished {
+            return None;
+        }
+        match self.matcher.next_match() {
+            Some((start, end)) => {
+                let piece = self.matcher.chunk.slice(self.start..start);
+                self.start = end;
+                Some(piece)
+            }
+            None => self.remainder(),
+        }
+    }
+}
+
+/// An iterator over substrings of a `StrChunk`, separated by
+/// a pattern and yielded in reverse order.
+///
+/// This struct is created by the `rsplit_chunks` method of `StrChunk`.
+#[derive(Clone, Debug)]
+pub struct RSplitChunks<P> {
+    matcher: RevMatcher<P>,
+    end: usize,
+    finished: bool,
+}
+
+impl<P: Pattern> RSplitChunks<P> {
+    pub(crate) fn new(chunk: StrChunk, pat: P) -> Self {
+        let end = chunk.len();
+        RSplitChunks {
+            matcher: RevMatcher::new(chunk, pat),
+            end,
+            finished: false,
+        }
+    }
+}
+
+impl<P: Pattern> Iterator for RSplitChunks<P> {
+    type Item = StrChunk;
+
+    fn next(&mut self) -> Option<StrChunk> {
+        if self.finished {
+            return None;
+        }
+        match self.matcher.next_match() {
+            Some((start, end)) => {
+                let piece = self.matcher.chunk.slice(end..self.end);
+                self.end = start;
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                Some(self.matcher.chunk.slice(..self.end))
+            }
+        }
+    }
+}
+
+/// An iterator over at most a given number of substrings of
+/// a `StrChunk`, separated by a pattern.
+///
+/// This struct is created by the `splitn_chunks` method of `StrChunk`.
+#[derive(Clone, Debug)]
+pub struct SplitNChunks<P> {
+    inner: SplitChunks<P>,
+    count: usize,
+}
+
+impl<P: Pattern> SplitNChunks<P> {
+    pub(crate) fn new(chunk: StrChunk, count: usize, pat: P) -> Self {
+        SplitNChunks {
+            inner: SplitChunks::new(chunk, pat),
+            count,
+        }
+    }
+}
+
+impl<P: Pattern> Iterator for SplitNChunks<P> {
+    type Item = StrChunk;
+
+    fn next(&mut self) -> Option<StrChunk> {
+        match self.count {
+            0 => None,
+            1 => {
+                self.count = 0;
+                self.inner.remainder()
+            }
+            _ => {
+                self.count -= 1;
+                self.inner.next()
+            }
+        }
+    }
+}
+
+/// An iterator over the matches of a pattern within a `StrChunk`.
+///
+/// This struct is created by the `matches_chunks` method of `StrChunk`.
+#[derive(Clone, Debug)]
+pub struct MatchesChunks<P> {
+    matcher: Matcher<P>,
+}
+
+impl<P: Pattern> MatchesChunks<P> {
+    pub(crate) fn new(chunk: StrChunk, pat: P) -> Self {
+        MatchesChunks {
+            matcher: Matcher::new(chunk, pat),
+        }
+    }
+}
+
+impl<P: Pattern> Iterator for MatchesChunks<P> {
+    type Item = StrChunk;
+
+    fn next(&mut self) -> Option<StrChunk> {
+        let (start, end) = self.matcher.next_match()?;
+        Some(self.matcher.chunk.slice(start..end))
+    }
+}
+
+/// An iterator over the matches of a pattern within a `StrChunk`,
+/// along with the byte indices of the matches.
+///
+/// This struct is created by the `match_indices_chunks` method
+/// of `StrChunk`.
+#[derive(Clone, Debug)]
+pub struct MatchIndicesChunks<P> {
+    matcher: Matcher<P>,
+}
+
+impl<P: Pattern> MatchIndicesChunks<P> {
+    pub(crate) fn new(chunk: StrChunk, pat: P) -> Self {
+        MatchIndicesChunks {
+            matcher: Matcher::new(chunk, pat),
+        }
+    }
+}
+
+impl<P: Pattern> Iterator for MatchIndicesChunks<P> {
+    type Item = (usize, StrChunk);
+
+    fn next(&mut self) -> Option<(usize, StrChunk)> {
+        let (start, end) = self.matcher.next_match()?;
+        Some((start, self.matcher.chunk.slice(start..end)))
+    }
+}
+
+/// The terminator of a line yielded by `LinesChunks`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// Line feed, `"\n"`.
+    Lf,
+    /// Carriage return followed by line feed, `"\r\n"`.
+    CrLf,
+    /// Carriage return not followed by line feed, `"\r"`.
+    Cr,
+    /// Next line, U+0085.
+    ///
+    /// Only recognized with `LinesChunks::unicode`.
+    Nel,
+    /// Line separator, U+2028.
+    ///
+    /// Only recognized with `LinesChunks::unicode`.
+    Ls,
+    /// Paragraph separator, U+2029.
+    ///
+    /// Only recognized with `LinesChunks::unicode`.
+    Ps,
+    /// No terminator: the line is at the end of the string.
+    None,
+}
+
+impl LineEnding {
+    /// Returns the terminator as a string slice.
+    ///
+    /// For `LineEnding::None`, the empty string is returned.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+            LineEnding::Nel => "\u{85}",
+            LineEnding::Ls => "\u{2028}",
+            LineEnding::Ps => "\u{2029}",
+            LineEnding::None => "",
+        }
+    }
+}
+
+/// An iterator over the lines of a `StrChunk`.
+///
+/// This struct is created by the `lines_chunks` method of `StrChunk`.
+/// Each line is yielded together with the terminator that ended it,
+/// so that concatenating the lines with their terminators reproduces
+/// the original string. An empty string produces no lines, and
+/// a terminator at the end of the string does not produce a trailing
+/// empty line.
+///
+/// By default, lines are terminated by `"\n"`, `"\r\n"`, or a lone `"\r"`.
+/// The `unicode` method enables recognition of the Unicode line
+/// terminators NEL, LS, and PS as well.
+///
+/// # Example
+///
+/// ```rust
+/// # use strchunk::StrChunk;
+/// use strchunk::iter::LineEnding;
+///
+/// let chunk = StrChunk::from("one\r\ntwo\n\nthree");
+/// let lines: Vec<_> = chunk.lines_chunks().collect();
+/// assert_eq!(
+///     lines,
+///     [
+///         ("one".into(), LineEnding::CrLf),
+///         ("two".into(), LineEnding::Lf),
+///         ("".into(), LineEnding::Lf),
+///         ("three".into(), LineEnding::None),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct LinesChunks {
+    chunk: StrChunk,
+    unicode: bool,
+}
+
+impl LinesChunks {
+    pub(crate) fn new(chunk: StrChunk) -> Self {
+        LinesChunks {
+            chunk,
+            unicode: false,
+        }
+    }
+
+    /// Enables recognition of the Unicode line terminators
+    /// NEL (U+0085), LS (U+2028), and PS (U+2029).
+    pub fn unicode(mut self) -> Self {
+        self.unicode = true;
+        self
+    }
+
+    // Finds the next line terminator, returning its position
+    // and the kind of the terminator.
+    fn find_terminator(&self) -> Option<(usize, LineEnding)> {
+        let bytes = self.chunk.as_bytes();
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let ending = match bytes[pos] {
+                b'\n' => Some(LineEnding::Lf),
+                b'\r' => match bytes.get(pos + 1) {
+                    Some(b'\n') => Some(LineEnding::CrLf),
+                    _ => Some(LineEnding::Cr),
+                },
+                0xC2 if self.unicode => match bytes[pos + 1] {
+                    0x85 => Some(LineEnding::Nel),
+                    _ => None,
+                },
+                0xE2 if self.unicode => match &bytes[pos + 1..pos + 3] {
+                    [0x80, 0xA8] => Some(LineEnding::Ls),
+                    [0x80, 0xA9] => Some(LineEnding::Ps),
+                    _ => None,
+                },
+                _ => None,
+            };
+            if let Some(ending) = ending {
+                return Some((pos, ending));
+            }
+            pos += 1;
+        }
+        None
+    }
+}
+
+impl Iterator for LinesChunks {
+    type Item = (StrChunk, LineEnding);
+
+    fn next(&mut self) -> Option<(StrChunk, LineEnding)> {
+        if self.chunk.is_empty() {
+            return None;
+        }
+        match self.find_terminator() {
+            Some((pos, ending)) => {
+                let line = self.chunk.split_to(pos);
+                self.chunk.split_to(ending.as_str().len());
+                Some((line, ending))
+            }
+            None => {
+                let line = self.chunk.split_off(0);
+                Some((line, LineEnding::None))
+            }
+        }
+    }
+}
+
+/// A draining iterator for `StrChunkMut`.
+///
+/// This struct is created by the `drain` method of `StrChunkMut`.
+/// The drained range is removed from the string when the iterator
+/// is dropped.
+#[derive(Debug)]
+pub struct Drain<'a> {
+    chunk: &'a mut StrChunkMut,
+    start: usize,
+    end: usize,
+    front: usize,
+    back: usize,
+}
+
+impl<'a> Drain<'a> {
+    pub(crate) fn new(
+        chunk: &'a mut StrChunkMut,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        Drain {
+            chunk,
+            start,
+            end,
+            front: start,
+            back: end,
+        }
+    }
+
+    /// Returns the remaining (sub)string of this iterator as a slice.
+    pub fn as_str(&self) -> &str {
+        &self.chunk[self.front..self.back]
+    }
+}
+
+impl Iterator for Drain<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next()?;
+        self.front += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len.div_ceil(4), Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Drain<'_> {
+    fn next_back(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.back -= ch.len_utf8();
+        Some(ch)
+    }
+}
+
+impl FusedIterator for Drain<'_> {}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        self.chunk.remove_bounded_range(self.start..self.end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_STR: &str = "Mary had a little lamb, little lamb";
+
+    fn collect<I: Iterator<Item = StrChunk>>(iter: I) -> Vec<String> {
+        iter.map(String::from).collect()
+    }
+
+    macro_rules! assert_same_as_str {
+        ($s:expr, $method:ident, $std_method:ident, $($arg:expr),+) => {
+            let chunk = StrChunk::from($s);
+            assert_eq!(
+                collect(chunk.$method($($arg),+)),
+                $s.$std_method($($arg),+).collect::<Vec<_>>(),
+                "{}({:?}) of {:?}",
+                stringify!($method),
+                ($($arg),+),
+                $s,
+            );
+        };
+    }
+
+    #[test]
+    fn split_same_as_str() {
+        for s in [TEST_STR, "", " ", "  lamb  ", "Привет, мир"] {
+            assert_same_as_str!(s, split_chunks, split, ' ');
+            assert_same_as_str!(s, split_chunks, split, "lamb");
+            assert_same_as_str!(s, split_chunks, split, "");
+            assert_same_as_str!(s, split_chunks, split, &[' ', ','][..]);
+            assert_same_as_str!(s, split_chunks, split, ['a', 'и']);
+            assert_same_as_str!(s, rsplit_chunks, rsplit, ' ');
+            assert_same_as_str!(s, rsplit_chunks, rsplit, "lamb");
+            assert_same_as_str!(s, rsplit_chunks, rsplit, "");
+            assert_same_as_str!(
+                s,
+                split_terminator_chunks,
+                split_terminator,
+                ' '
+            );
+            assert_same_as_str!(
+                s,
+                split_terminator_chunks,
+                split_terminator,
+                ""
+            );
+            assert_same_as_str!(s, matches_chunks, matches, "lamb");
+            assert_same_as_str!(s, matches_chunks, matches, "");
+            for n in 0..4 {
+                assert_same_as_str!(s, splitn_chunks, splitn, n, ' ');
+            }
+        }
+    }
+
+    #[test]
+    fn split_with_closure() {
+        let chunk = StrChunk::from("a1b22c");
+        let pieces = collect(chunk.split_chunks(|c: char| c.is_numeric()));
+        assert_eq!(pieces, ["a", "b", "", "c"]);
+    }
+
+    #[test]
+    fn split_with_string_ref() {
+        let chunk = StrChunk::from("a, b, c");
+        let sep = String::from(", ");
+        let pieces = collect(chunk.split_chunks(&sep));
+        assert_eq!(pieces, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn split_shares_buffer() {
+        let chunk = StrChunk::from("a,b");
+        let pieces = chunk.split_chunks(',').collect::<Vec<_>>();
+        assert_eq!(pieces[1].as_ptr(), chunk[2..].as_ptr());
+    }
+
+    fn reconstruct(lines: LinesChunks) -> String {
+        lines.fold(String::new(), |mut s, (line, ending)| {
+            s.push_str(&line);
+            s.push_str(ending.as_str());
+            s
+        })
+    }
+
+    #[test]
+    fn lines() {
+        let chunk = StrChunk::from("a\rb\r\n\nc\u{85}d\u{2028}e\r");
+        let lines = chunk.lines_chunks().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                ("a".into(), LineEnding::Cr),
+                ("b".into(), LineEnding::CrLf),
+                ("".into(), LineEnding::Lf),
+                ("c\u{85}d\u{2028}e".into(), LineEnding::Cr),
+            ]
+        );
+        assert_eq!(reconstruct(chunk.lines_chunks()), chunk);
+        assert_eq!(lines[0].0.as_ptr(), chunk.as_ptr());
+    }
+
+    #[test]
+    fn lines_unicode() {
+        let chunk = StrChunk::from("a\u{85}b\u{2028}c\u{2029}\u{2030}\u{C5}");
+        let lines = chunk.lines_chunks().unicode().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                ("a".into(), LineEnding::Nel),
+                ("b".into(), LineEnding::Ls),
+                ("c".into(), LineEnding::Ps),
+                ("\u{2030}\u{C5}".into(), LineEnding::None),
+            ]
+        );
+        assert_eq!(reconstruct(chunk.lines_chunks().unicode()), chunk);
+    }
+
+    #[test]
+    fn lines_same_as_str() {
+        for s in ["", "\n", "a", "a\n", "a\n\nb", "a\r\nb\r\n"] {
+            let chunk = StrChunk::from(s);
+            let lines = chunk.lines_chunks().map(|(line, _)| line);
+            assert_eq!(collect(lines), s.lines().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn match_indices() {
+        let chunk = StrChunk::from("Привет, привет");
+        let matches = chunk
+            .match_indices_chunks("ривет")
+            .map(|(i, m)| (i, String::from(m)))
+            .collect::<Vec<_>>();
+        assert_eq!(matches, [(2, "ривет".into()), (16, "ривет".into())]);
+    }
+}
@@ -5,6 +5,15 @@
 //! wrap around `Bytes` and `BytesMut`, respectively, adding a guarantee
 //! for the content to be valid UTF-8 to make it safely usable as
 //! Rust string slices.
+//!
+//! # Optional features
+//!
+//! - `futures-io`: adapters for asynchronous I/O with `futures::io`,
+//!   provided in the `futures_io` module.
+//! - `tokio`: adapters for asynchronous I/O with `tokio`, provided in
+//!   the `tokio` module.
+//! - `tokio-util`: codecs for framed I/O with `tokio-util`, provided in
+//!   the `codec` module.
 
 #![cfg_attr(feature = "specialization", feature(min_specialization))]
 #![warn(rust_2018_idioms)]
@@ -13,7 +22,25 @@
 
 mod chunk;
 mod chunk_mut;
+mod decoder;
 mod impls;
+mod reader;
+
+pub mod buf;
+#[cfg(feature = "tokio-util")]
+pub mod codec;
+#[cfg(feature = "futures-io")]
+pub mod futures_io;
+pub mod io;
+pub mod iter;
+pub mod list;
+pub mod pattern;
+pub mod rope;
+#[cfg(feature = "tokio")]
+pub mod tokio;
 
-pub use crate::chunk::{ExtractUtf8Error, StrChunk};
+pub use crate::chunk::{
+    BoundaryError, ExtractUtf8Error, FromUtf8Error, StrChunk,
+};
 pub use crate::chunk_mut::StrChunkMut;
+pub use crate::decoder::Utf8Decoder;
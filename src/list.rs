@@ -0,0 +1,413 @@
+//! A sequence of `StrChunk` pieces readable as a byte buffer.
+
+use crate::{StrChunk, StrChunkMut};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use std::collections::vec_deque::{self, VecDeque};
+use std::fmt::{self, Display};
+use std::io::IoSlice;
+use std::iter::FromIterator;
+use std::str;
+
+/// A sequence of `StrChunk` pieces, implementing `bytes::Buf`.
+///
+/// `StrChunkList` allows a string to be assembled out of many pieces
+/// without copying them into a single buffer. The content can be
+/// consumed through the `Buf` implementation, which provides all pieces
+/// at once to vectored writes with `chunks_vectored`. The list keeps
+/// track of the total length of its content.
+///
+/// The `Buf` methods may advance the list past a part of a UTF-8
+/// sequence. The methods providing access to the content as text,
+/// such as `chars` and the `Display` implementation, skip the rest
+/// of a partially consumed sequence.
+///
+/// # Example
+///
+/// ```rust
+/// # use strchunk::StrChunk;
+/// use bytes::Buf;
+/// use strchunk::list::StrChunkList;
+///
+/// let mut list = StrChunkList::new();
+/// list.push(StrChunk::from("Hello"));
+/// list.push(StrChunk::from(", "));
+/// list.push(StrChunk::from("world"));
+/// assert_eq!(list.len(), 12);
+/// assert_eq!(list.to_string(), "Hello, world");
+///
+/// list.advance(7);
+/// assert_eq!(list.chunk(), b"world");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StrChunkList {
+    pieces: VecDeque<StrChunk>,
+    // The number of bytes consumed from the front piece.
+    offset: usize,
+    len: usize,
+}
+
+impl StrChunkList {
+    /// Creates a new empty `StrChunkList`.
+    #[inline]
+    pub fn new() -> Self {
+        StrChunkList::default()
+    }
+
+    /// Returns the total length of the content in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the list has no content.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a piece to the end of the list.
+    ///
+    /// Empty pieces are not added to the list.
+    pub fn push(&mut self, chunk: StrChunk) {
+        if !chunk.is_empty() {
+            self.len += chunk.len();
+            self.pieces.push_back(chunk);
+        }
+    }
+
+    /// Removes all content from the list.
+    pub fn clear(&mut self) {
+        self.pieces.clear();
+        self.offset = 0;
+        self.len = 0;
+    }
+
+    /// Returns an iterator over the chars of the content.
+    pub fn chars(&self) -> Chars<'_> {
+        let mut pieces = self.pieces.iter();
+        let front = pieces.next().map_or("", |chunk| {
+            let start = self.text_start(chunk);
+            &chunk[start..]
+        });
+        Chars {
+            pieces,
+            chars: front.chars(),
+        }
+    }
+
+    /// Returns an iterator over the lines of the content.
+    ///
+    /// Lines are split at line endings that are either newlines (`\n`)
+    /// or sequences of a carriage return followed by a line feed
+    /// (`\r\n`), like with `str::lines`. A line contained in a single
+    /// piece is yielded without copying; a line spanning multiple
+    /// pieces is copied into a new buffer.
+    pub fn lines(&self) -> Lines<'_> {
+        let mut pieces = self.pieces.iter();
+        let current = pieces.next().map_or_else(StrChunk::new, |chunk| {
+            let start = self.text_start(chunk);
+            chunk.slice(start..)
+        });
+        Lines { pieces, current }
+    }
+
+    // Returns the offset in the front piece at which the text content
+    // starts, skipping the rest of a partially consumed UTF-8 sequence.
+    fn text_start(&self, front: &StrChunk) -> usize {
+        (self.offset..=front.len())
+            .find(|&i| front.is_char_boundary(i))
+            .unwrap()
+    }
+
+    fn byte_slices(&self) -> impl Iterator<Item = &[u8]> {
+        self.pieces.iter().enumerate().map(move |(i, chunk)| {
+            let bytes = chunk.as_bytes();
+            if i == 0 {
+                &bytes[self.offset..]
+            } else {
+                bytes
+            }
+        })
+    }
+}
+
+impl Buf for StrChunkList {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len
+    }
+
+    fn chunk(&self) -> &[u8] {
+        match self.pieces.front() {
+            None => &[],
+            Some(chunk) => &chunk.as_bytes()[self.offset..],
+        }
+    }
+
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let mut n = 0;
+        for (slot, bytes) in dst.iter_mut().zip(self.byte_slices()) {
+            *slot = IoSlice::new(bytes);
+            n += 1;
+        }
+        n
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        assert!(
+            cnt <= self.len,
+            "cannot advance past the remaining length of {}",
+            self.len
+        );
+        self.len -= cnt;
+        while cnt != 0 {
+            let front_len = self.pieces[0].len() - self.offset;
+            if cnt < front_len {
+                self.offset += cnt;
+                return;
+            }
+            cnt -= front_len;
+            self.pieces.pop_front();
+            self.offset = 0;
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        match self.pieces.front() {
+            Some(chunk) if self.offset + len <= chunk.len() => {
+                let bytes = Bytes::from(chunk.clone())
+                    .slice(self.offset..self.offset + len);
+                self.advance(len);
+                bytes
+            }
+            _ => {
+                assert!(len <= self.len, "`len` greater than remaining");
+                let mut buf = BytesMut::with_capacity(len);
+                buf.put((&mut *self).take(len));
+                buf.freeze()
+            }
+        }
+    }
+}
+
+impl Display for StrChunkList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut pieces = self.pieces.iter();
+        if let Some(chunk) = pieces.next() {
+            let start = self.text_start(chunk);
+            f.write_str(&chunk[start..])?;
+        }
+        for chunk in pieces {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<StrChunk> for StrChunkList {
+    fn from_iter<T>(iterable: T) -> Self
+    where
+        T: IntoIterator<Item = StrChunk>,
+    {
+        let mut list = StrChunkList::new();
+        list.extend(iterable);
+        list
+    }
+}
+
+impl Extend<StrChunk> for StrChunkList {
+    fn extend<T>(&mut self, iterable: T)
+    where
+        T: IntoIterator<Item = StrChunk>,
+    {
+        for chunk in iterable {
+            self.push(chunk);
+        }
+    }
+}
+
+impl From<StrChunk> for StrChunkList {
+    fn from(chunk: StrChunk) -> Self {
+        let mut list = StrChunkList::new();
+        list.push(chunk);
+        list
+    }
+}
+
+/// An iterator over the chars of a `StrChunkList`.
+///
+/// This struct is created by the `chars` method of `StrChunkList`.
+#[derive(Clone, Debug)]
+pub struct Chars<'a> {
+    pieces: vec_deque::Iter<'a, StrChunk>,
+    chars: str::Chars<'a>,
+}
+
+impl Iterator for Chars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(c) = self.chars.next() {
+                return Some(c);
+            }
+            self.chars = self.pieces.next()?.chars();
+        }
+    }
+}
+
+/// An iterator over the lines of a `StrChunkList`.
+///
+/// This struct is created by the `lines` method of `StrChunkList`.
+#[derive(Clone, Debug)]
+pub struct Lines<'a> {
+    pieces: vec_deque::Iter<'a, StrChunk>,
+    // The unconsumed part of the current piece.
+    current: StrChunk,
+}
+
+impl Iterator for Lines<'_> {
+    type Item = StrChunk;
+
+    fn next(&mut self) -> Option<StrChunk> {
+        let mut line = LineBuf::default();
+        loop {
+            if let Some(pos) = self.current.find('\n') {
+                line.push(self.current.split_to(pos));
+                self.current.split_to(1);
+                return Some(strip_cr(line.finish().unwrap_or_default()));
+            }
+            line.push(self.current.split_off(0));
+            match self.pieces.next() {
+                Some(chunk) => self.current = chunk.clone(),
+                // The last line has no terminator.
+                None => return line.finish(),
+            }
+        }
+    }
+}
+
+// Accumulates the parts of a line, copying them only if the line
+// spans multiple pieces.
+#[derive(Default)]
+struct LineBuf {
+    line: Option<StrChunk>,
+    buf: Option<StrChunkMut>,
+}
+
+impl LineBuf {
+    fn push(&mut self, part: StrChunk) {
+        if part.is_empty() {
+            return;
+        }
+        if let Some(buf) = &mut self.buf {
+            buf.push_str(&part);
+            return;
+        }
+        match self.line.take() {
+            None => self.line = Some(part),
+            Some(prev) => {
+                let mut buf =
+                    StrChunkMut::with_capacity(prev.len() + part.len());
+                buf.push_str(&prev);
+                buf.push_str(&part);
+                self.buf = Some(buf);
+            }
+        }
+    }
+
+    fn finish(self) -> Option<StrChunk> {
+        self.buf.map(StrChunkMut::freeze).or(self.line)
+    }
+}
+
+fn strip_cr(mut line: StrChunk) -> StrChunk {
+    if line.ends_with('\r') {
+        line.truncate(line.len() - 1);
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(pieces: &[&'static str]) -> StrChunkList {
+        pieces.iter().map(|s| StrChunk::from(*s)).collect()
+    }
+
+    #[test]
+    fn buf_reads_across_pieces() {
+        let mut list = list(&["Hello", "", ", ", "world"]);
+        assert_eq!(list.remaining(), 12);
+        assert_eq!(list.chunk(), b"Hello");
+        list.advance(3);
+        assert_eq!(list.chunk(), b"lo");
+        list.advance(4);
+        assert_eq!(list.chunk(), b"world");
+        assert_eq!(list.len(), 5);
+        list.advance(5);
+        assert!(list.is_empty());
+        assert_eq!(list.chunk(), b"");
+    }
+
+    #[test]
+    #[should_panic]
+    fn advance_panics_past_end() {
+        list(&["Hello"]).advance(6);
+    }
+
+    #[test]
+    fn chunks_vectored() {
+        let mut list = list(&["Hello", ", ", "world"]);
+        list.advance(1);
+        let mut slices = [IoSlice::new(&[]); 4];
+        assert_eq!(list.chunks_vectored(&mut slices), 3);
+        assert_eq!(&*slices[0], b"ello");
+        assert_eq!(&*slices[2], b"world");
+        let mut slices = [IoSlice::new(&[]); 2];
+        assert_eq!(list.chunks_vectored(&mut slices), 2);
+    }
+
+    #[test]
+    fn copy_to_bytes() {
+        let hello = StrChunk::from("Hello");
+        let mut list = StrChunkList::from(hello.clone());
+        list.push(StrChunk::from(", world"));
+        let bytes = list.copy_to_bytes(4);
+        assert_eq!(bytes, "Hell");
+        assert_eq!(bytes.as_ptr(), hello.as_ptr());
+        let bytes = list.copy_to_bytes(3);
+        assert_eq!(bytes, "o, ");
+        assert_eq!(list.to_string(), "world");
+    }
+
+    #[test]
+    fn text_after_partial_advance() {
+        let mut list = list(&["При", "вет"]);
+        list.advance(1);
+        assert_eq!(list.to_string(), "ривет");
+        assert_eq!(list.chars().collect::<String>(), "ривет");
+        assert_eq!(list.lines().collect::<Vec<_>>(), ["ривет"]);
+    }
+
+    #[test]
+    fn lines_across_pieces() {
+        let list = list(&["one\ntw", "o\r", "\nthree", "\n", "\nfo", "ur"]);
+        let lines = list.lines().collect::<Vec<_>>();
+        assert_eq!(lines, ["one", "two", "three", "", "four"]);
+        let text = list.to_string();
+        assert_eq!(lines, text.lines().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lines_in_single_piece_are_not_copied() {
+        let chunk = StrChunk::from("one\ntwo\n");
+        let list = StrChunkList::from(chunk.clone());
+        let lines = list.lines().collect::<Vec<_>>();
+        assert_eq!(lines, ["one", "two"]);
+        assert_eq!(lines[1].as_ptr(), chunk[4..].as_ptr());
+    }
+}
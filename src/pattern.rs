@@ -0,0 +1,226 @@
+//! String patterns for the searching methods of `StrChunk`.
+//!
+//! The `Pattern` trait of the standard library is not stable, so this
+//! crate provides its own. It is implemented for the same types as
+//! the standard `Pattern`, so the searching methods of `StrChunk` accept
+//! the same arguments as their counterparts on `str`.
+
+/// A string pattern.
+///
+/// This trait is implemented for:
+///
+/// - `char`, matching the character;
+/// - `&str`, `&&str` and `&String`, matching the substring;
+/// - `&[char]`, `[char; N]` and `&[char; N]`, matching any of
+///   the characters;
+/// - `F: FnMut(char) -> bool`, matching characters for which
+///   the closure returns `true`.
+///
+/// The trait is sealed and cannot be implemented outside of this crate.
+pub trait Pattern: private::Searcher {}
+
+pub(crate) mod private {
+    // The searching methods are placed in a trait that cannot be named
+    // outside of the crate, so that they are not part of the public API.
+    pub trait Searcher {
+        // Returns the byte range of the first match in the haystack.
+        fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)>;
+
+        // Returns the byte range of the last match in the haystack.
+        fn rfind_in(&mut self, haystack: &str) -> Option<(usize, usize)>;
+
+        // Returns the length of the match at the start of the haystack.
+        fn prefix_len(&mut self, haystack: &str) -> Option<usize>;
+
+        // Returns the length of the match at the end of the haystack.
+        fn suffix_len(&mut self, haystack: &str) -> Option<usize>;
+    }
+}
+
+use self::private::Searcher;
+
+// Extends the position of a matched character to its byte range.
+fn char_match(haystack: &str, pos: usize) -> (usize, usize) {
+    let c = haystack[pos..].chars().next().unwrap();
+    (pos, pos + c.len_utf8())
+}
+
+impl Pattern for char {}
+
+impl Searcher for char {
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        let pos = haystack.find(*self)?;
+        Some((pos, pos + self.len_utf8()))
+    }
+
+    fn rfind_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        let pos = haystack.rfind(*self)?;
+        Some((pos, pos + self.len_utf8()))
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        haystack.starts_with(*self).then_some(self.len_utf8())
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        haystack.ends_with(*self).then_some(self.len_utf8())
+    }
+}
+
+impl Pattern for &str {}
+
+impl Searcher for &str {
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        let pos = haystack.find(*self)?;
+        Some((pos, pos + self.len()))
+    }
+
+    fn rfind_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        let pos = haystack.rfind(*self)?;
+        Some((pos, pos + self.len()))
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        haystack.starts_with(*self).then_some(self.len())
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        haystack.ends_with(*self).then_some(self.len())
+    }
+}
+
+macro_rules! impl_pattern_via_str {
+    (impl<$a:lifetime, $b:lifetime> for $T:ty) => {
+        impl<$a, $b> Pattern for $T {}
+
+        impl<$a, $b> Searcher for $T {
+            fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+                let mut pat: &str = self;
+                pat.find_in(haystack)
+            }
+
+            fn rfind_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+                let mut pat: &str = self;
+                pat.rfind_in(haystack)
+            }
+
+            fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+                let mut pat: &str = self;
+                pat.prefix_len(haystack)
+            }
+
+            fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+                let mut pat: &str = self;
+                pat.suffix_len(haystack)
+            }
+        }
+    };
+}
+
+impl_pattern_via_str!(impl<'a, 'b> for &'a &'b str);
+impl_pattern_via_str!(impl<'a, 'b> for &'a String);
+
+macro_rules! impl_pattern_for_chars {
+    ([$($param:tt)*] for $T:ty) => {
+        impl<$($param)*> Pattern for $T {}
+
+        impl<$($param)*> Searcher for $T {
+            fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+                let chars: &[char] = &self[..];
+                let pos = haystack.find(chars)?;
+                Some(char_match(haystack, pos))
+            }
+
+            fn rfind_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+                let chars: &[char] = &self[..];
+                let pos = haystack.rfind(chars)?;
+                Some(char_match(haystack, pos))
+            }
+
+            fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+                let c = haystack.chars().next()?;
+                self.contains(&c).then_some(c.len_utf8())
+            }
+
+            fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+                let c = haystack.chars().next_back()?;
+                self.contains(&c).then_some(c.len_utf8())
+            }
+        }
+    };
+}
+
+impl_pattern_for_chars!(['a] for &'a [char]);
+impl_pattern_for_chars!([const N: usize] for [char; N]);
+impl_pattern_for_chars!(['a, const N: usize] for &'a [char; N]);
+
+impl<F> Pattern for F where F: FnMut(char) -> bool {}
+
+impl<F> Searcher for F
+where
+    F: FnMut(char) -> bool,
+{
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        let pos = haystack.find(&mut *self)?;
+        Some(char_match(haystack, pos))
+    }
+
+    fn rfind_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        let pos = haystack.rfind(&mut *self)?;
+        Some(char_match(haystack, pos))
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        let c = haystack.chars().next()?;
+        self(c).then_some(c.len_utf8())
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        let c = haystack.chars().next_back()?;
+        self(c).then_some(c.len_utf8())
+    }
+}
+
+// Counterparts of the `str` methods taking a pattern, used
+// to implement the methods of `StrChunk`.
+
+pub(crate) fn trim_start_matches<'a, P: Pattern>(
+    mut s: &'a str,
+    pat: &mut P,
+) -> &'a str {
+    // An empty match would not make progress
+    while let Some(len @ 1..) = pat.prefix_len(s) {
+        s = &s[len..];
+    }
+    s
+}
+
+pub(crate) fn trim_end_matches<'a, P: Pattern>(
+    mut s: &'a str,
+    pat: &mut P,
+) -> &'a str {
+    while let Some(len @ 1..) = pat.suffix_len(s) {
+        s = &s[..s.len() - len];
+    }
+    s
+}
+
+pub(crate) fn trim_matches<'a, P: Pattern>(s: &'a str, pat: &mut P) -> &'a str {
+    trim_end_matches(trim_start_matches(s, pat), pat)
+}
+
+pub(crate) fn strip_prefix<'a, P: Pattern>(
+    s: &'a str,
+    pat: &mut P,
+) -> Option<&'a str> {
+    let len = pat.prefix_len(s)?;
+    Some(&s[len..])
+}
+
+pub(crate) fn strip_suffix<'a, P: Pattern>(
+    s: &'a str,
+    pat: &mut P,
+) -> Option<&'a str> {
+    let len = pat.suffix_len(s)?;
+    Some(&s[..s.len() - len])
+}
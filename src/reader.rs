@@ -0,0 +1,332 @@
+use crate::{StrChunk, Utf8Decoder};
+
+use bytes::BytesMut;
+
+use std::io;
+use std::mem::MaybeUninit;
+use std::task::{ready, Poll};
+
+pub(crate) const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
+
+/// The decoding core shared by the UTF-8 reader adapters.
+///
+/// Byte input is read into the buffer, then the complete UTF-8 content
+/// is extracted from it, leaving a possibly incomplete sequence
+/// at the end to be completed by the next read.
+#[derive(Debug)]
+pub(crate) struct ReadBuffer {
+    buf: BytesMut,
+    decoder: Utf8Decoder,
+    capacity: usize,
+    // The length of the spare capacity of the buffer that has been
+    // initialized for reads requiring an initialized buffer.
+    initialized: usize,
+    resume: bool,
+}
+
+impl ReadBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        // A buffer must be able to hold at least one complete
+        // UTF-8 sequence to make progress.
+        assert!(capacity >= 4, "buffer capacity must be at least 4 bytes");
+        ReadBuffer {
+            buf: BytesMut::with_capacity(capacity),
+            decoder: Utf8Decoder::new(),
+            capacity,
+            initialized: 0,
+            resume: false,
+        }
+    }
+
+    /// Decodes UTF-8 content, calling `read` to append more input
+    /// to the buffer as needed.
+    ///
+    /// The `read` closure returns the number of bytes read, with 0
+    /// signifying the end of input. A non-empty chunk is returned
+    /// unless the end of input has been reached.
+    #[cfg(any(feature = "tokio", feature = "futures-io"))]
+    pub fn poll_decode<F>(&mut self, mut read: F) -> Poll<io::Result<StrChunk>>
+    where
+        F: FnMut(&mut BytesMut) -> Poll<io::Result<usize>>,
+    {
+        self.poll_decode_with(|buf, _| read(buf))
+    }
+
+    /// Decodes UTF-8 content like `poll_decode`, with the `read` closure
+    /// reading into an initialized slice.
+    ///
+    /// The spare capacity of the buffer is zeroed only once after it has
+    /// been allocated, rather than for every read.
+    pub fn poll_decode_initialized<F>(
+        &mut self,
+        mut read: F,
+    ) -> Poll<io::Result<StrChunk>>
+    where
+        F: FnMut(&mut [u8]) -> Poll<io::Result<usize>>,
+    {
+        self.poll_decode_with(|buf, initialized| {
+            read_initialized(buf, initialized, &mut read)
+        })
+    }
+
+    fn poll_decode_with<F>(&mut self, mut read: F) -> Poll<io::Result<StrChunk>>
+    where
+        F: FnMut(&mut BytesMut, &mut usize) -> Poll<io::Result<usize>>,
+    {
+        if self.resume {
+            // The input remaining after a skipped invalid sequence
+            // may contain complete content.
+            self.resume = false;
+            if let Some(res) = self.extract() {
+                return Poll::Ready(res);
+            }
+        }
+        loop {
+            // Keep enough room for a read to make good progress,
+            // rather than filling the remaining few bytes.
+            if self.buf.capacity() - self.buf.len() < self.capacity / 2 {
+                self.buf.reserve(self.capacity);
+                // The buffer may have been reallocated
+                self.initialized = 0;
+            }
+            let bytes_read =
+                ready!(read(&mut self.buf, &mut self.initialized))?;
+            if bytes_read == 0 {
+                return Poll::Ready(self.finish());
+            }
+            if let Some(res) = self.extract() {
+                return Poll::Ready(res);
+            }
+        }
+    }
+
+    fn extract(&mut self) -> Option<io::Result<StrChunk>> {
+        let res = decode_utf8(&mut self.decoder, &mut self.buf);
+        match res {
+            Ok(ref chunk) if chunk.is_empty() => None,
+            Ok(_) => Some(res),
+            Err(_) => {
+                self.resume = true;
+                Some(res)
+            }
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<StrChunk> {
+        if self.buf.is_empty() {
+            return Ok(StrChunk::new());
+        }
+        // Discard the dangling bytes so that subsequent reads
+        // report the end of input.
+        self.buf.clear();
+        self.decoder.reset();
+        Err(incomplete_utf8_error())
+    }
+}
+
+// Reads into the spare capacity of `buf`, zeroing the part of it
+// that has not been initialized by previous calls. Extracting content
+// from the front of the buffer does not affect the spare capacity,
+// so `initialized` remains valid until the buffer is reallocated.
+fn read_initialized<F>(
+    buf: &mut BytesMut,
+    initialized: &mut usize,
+    read: F,
+) -> Poll<io::Result<usize>>
+where
+    F: FnOnce(&mut [u8]) -> Poll<io::Result<usize>>,
+{
+    let spare = buf.spare_capacity_mut();
+    let init_len = (*initialized).min(spare.len());
+    spare[init_len..].fill(MaybeUninit::new(0));
+    *initialized = spare.len();
+    // Safety: the spare capacity has been initialized entirely.
+    let dst = unsafe { &mut *(spare as *mut [MaybeUninit<u8>] as *mut [u8]) };
+    let bytes_read = ready!(read(dst))?;
+    assert!(bytes_read <= dst.len(), "reader returned an invalid length");
+    *initialized -= bytes_read;
+    // Safety: the bytes have been initialized and filled by the read.
+    unsafe {
+        buf.set_len(buf.len() + bytes_read);
+    }
+    Poll::Ready(Ok(bytes_read))
+}
+
+/// Extracts UTF-8 content from `buf`, mapping an invalid sequence
+/// to an I/O error of kind `InvalidData`.
+///
+/// The invalid sequence is skipped so that decoding can resume
+/// after the error.
+pub(crate) fn decode_utf8(
+    decoder: &mut Utf8Decoder,
+    buf: &mut BytesMut,
+) -> io::Result<StrChunk> {
+    decoder
+        .decode(buf)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub(crate) fn incomplete_utf8_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "incomplete UTF-8 sequence in input",
+    )
+}
+
+// Defines a UTF-8 reader adapter type for an asynchronous I/O trait.
+//
+// The generated type wraps a reader bounded by the given trait
+// and a `ReadBuffer`, and provides the constructors, the accessors,
+// the `poll_read_utf8` and `read_utf8` methods, and a `Stream`
+// implementation. The `poll_decode` function reads from the pinned
+// reader and decodes the input with the buffer; it is the only part
+// that differs between the I/O traits.
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+macro_rules! async_utf8_reader {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident<R: $bound:path> {
+            poll_decode: $poll_decode:path,
+        }
+    ) => {
+        ::pin_project_lite::pin_project! {
+            $(#[$attr])*
+            #[derive(Debug)]
+            pub struct $name<R> {
+                #[pin]
+                inner: R,
+                buf: $crate::reader::ReadBuffer,
+            }
+        }
+
+        impl<R> $name<R> {
+            #[doc = concat!(
+                "Creates a new `", stringify!($name),
+                "` with the default buffer capacity."
+            )]
+            ///
+            /// The default capacity is currently 8 KiB, but this may change
+            /// in the future.
+            pub fn new(inner: R) -> Self {
+                $name::with_capacity(
+                    $crate::reader::DEFAULT_BUFFER_CAPACITY,
+                    inner,
+                )
+            }
+
+            #[doc = concat!(
+                "Creates a new `", stringify!($name),
+                "` with the specified buffer capacity."
+            )]
+            ///
+            /// # Panics
+            ///
+            /// Panics if `capacity` is less than 4, which is not enough to hold
+            /// every possible UTF-8 sequence.
+            pub fn with_capacity(capacity: usize, inner: R) -> Self {
+                $name {
+                    inner,
+                    buf: $crate::reader::ReadBuffer::with_capacity(capacity),
+                }
+            }
+
+            /// Gets a reference to the underlying reader.
+            pub fn get_ref(&self) -> &R {
+                &self.inner
+            }
+
+            /// Gets a mutable reference to the underlying reader.
+            ///
+            /// Reading directly from the underlying reader would corrupt
+            /// the decoded text.
+            pub fn get_mut(&mut self) -> &mut R {
+                &mut self.inner
+            }
+
+            /// Gets a pinned mutable reference to the underlying reader.
+            ///
+            /// Reading directly from the underlying reader would corrupt
+            /// the decoded text.
+            pub fn get_pin_mut(
+                self: ::std::pin::Pin<&mut Self>,
+            ) -> ::std::pin::Pin<&mut R> {
+                self.project().inner
+            }
+
+            #[doc = concat!(
+                "Consumes the `", stringify!($name),
+                "`, returning the underlying reader."
+            )]
+            ///
+            /// Any input buffered but not yet decoded is lost.
+            pub fn into_inner(self) -> R {
+                self.inner
+            }
+        }
+
+        impl<R: $bound> $name<R> {
+            /// Attempts to read and decode UTF-8 content from the underlying
+            /// reader.
+            ///
+            /// On success, returns `Poll::Ready(Ok(chunk))` with a non-empty
+            /// chunk of text, or an empty chunk if the end of input has been
+            /// reached.
+            ///
+            /// # Errors
+            ///
+            /// I/O errors of the underlying reader are passed through,
+            /// including errors of kind `Interrupted`, after which
+            /// reading can be resumed.
+            /// An invalid UTF-8 sequence in the input results in an error
+            /// of kind `InvalidData` wrapping an `ExtractUtf8Error` that holds
+            /// the text decoded before the invalid sequence; the invalid
+            /// sequence is skipped so that reading can be resumed.
+            /// An incomplete UTF-8 sequence at the end of input is also
+            /// reported as an `InvalidData` error.
+            pub fn poll_read_utf8(
+                self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context<'_>,
+            ) -> ::std::task::Poll<::std::io::Result<$crate::StrChunk>> {
+                let this = self.project();
+                $poll_decode(this.inner, cx, this.buf)
+            }
+        }
+
+        impl<R: $bound + Unpin> $name<R> {
+            /// Reads and decodes UTF-8 content from the underlying reader.
+            ///
+            /// This is an async version of `poll_read_utf8`. An empty chunk
+            /// is returned when the end of input has been reached.
+            ///
+            /// # Errors
+            ///
+            /// See the documentation of `poll_read_utf8`.
+            pub async fn read_utf8(
+                &mut self,
+            ) -> ::std::io::Result<$crate::StrChunk> {
+                ::std::future::poll_fn(|cx| {
+                    ::std::pin::Pin::new(&mut *self).poll_read_utf8(cx)
+                })
+                .await
+            }
+        }
+
+        impl<R: $bound> ::futures_core::Stream for $name<R> {
+            type Item = ::std::io::Result<$crate::StrChunk>;
+
+            fn poll_next(
+                self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context<'_>,
+            ) -> ::std::task::Poll<Option<Self::Item>> {
+                use ::std::task::Poll;
+                match ::std::task::ready!(self.poll_read_utf8(cx)) {
+                    Ok(chunk) if chunk.is_empty() => Poll::Ready(None),
+                    res => Poll::Ready(Some(res)),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub(crate) use async_utf8_reader;
@@ -0,0 +1,921 @@
+//! A rope data structure with `StrChunk` leaves.
+//!
+//! `Rope` represents a string as a balanced binary tree, with
+//! the content held in `StrChunk` leaves. Editing a rope reuses
+//! the leaves outside of the edited range, so that large documents
+//! can be edited without copying their content, and clones of a rope
+//! share the tree nodes.
+
+use crate::chunk::resolve_range;
+use crate::{StrChunk, StrChunkMut};
+
+use std::fmt::{self, Debug, Display};
+use std::iter::FromIterator;
+use std::ops::RangeBounds;
+use std::str;
+use std::sync::Arc;
+
+// The maximum length of a leaf created when splitting up a large chunk
+// or merging small leaves at the site of an edit.
+// Leaves are bounded in size so that operations with char offsets,
+// which need to scan the content of a leaf, are not too costly.
+const MAX_LEAF_LEN: usize = 1024;
+
+#[derive(Debug)]
+enum Node {
+    Leaf {
+        chunk: StrChunk,
+        chars: usize,
+    },
+    Branch {
+        left: Arc<Node>,
+        right: Arc<Node>,
+        len: usize,
+        chars: usize,
+        height: usize,
+    },
+}
+
+// A tree, possibly empty. Non-empty trees never have empty leaves.
+type Tree = Option<Arc<Node>>;
+
+impl Node {
+    fn leaf(chunk: StrChunk) -> Arc<Node> {
+        debug_assert!(!chunk.is_empty());
+        let chars = chunk.chars().count();
+        Arc::new(Node::Leaf { chunk, chars })
+    }
+
+    fn branch(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        Arc::new(Node::Branch {
+            len: left.len() + right.len(),
+            chars: left.chars() + right.chars(),
+            height: left.height().max(right.height()) + 1,
+            left,
+            right,
+        })
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf { chunk, .. } => chunk.len(),
+            Node::Branch { len, .. } => *len,
+        }
+    }
+
+    fn chars(&self) -> usize {
+        match self {
+            Node::Leaf { chars, .. } | Node::Branch { chars, .. } => *chars,
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 0,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+
+    fn first_leaf(&self) -> &StrChunk {
+        match self {
+            Node::Leaf { chunk, .. } => chunk,
+            Node::Branch { left, .. } => left.first_leaf(),
+        }
+    }
+
+    fn last_leaf(&self) -> &StrChunk {
+        match self {
+            Node::Leaf { chunk, .. } => chunk,
+            Node::Branch { right, .. } => right.last_leaf(),
+        }
+    }
+
+    fn children(&self) -> (Arc<Node>, Arc<Node>) {
+        match self {
+            Node::Branch { left, right, .. } => (left.clone(), right.clone()),
+            Node::Leaf { .. } => unreachable!("leaf node has no children"),
+        }
+    }
+}
+
+// Creates a branch of two trees whose heights differ by at most 2,
+// rotating the nodes to restore the balance if needed.
+fn balance(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+    if left.height() > right.height() + 1 {
+        let (ll, lr) = left.children();
+        if ll.height() >= lr.height() {
+            Node::branch(ll, Node::branch(lr, right))
+        } else {
+            let (lrl, lrr) = lr.children();
+            Node::branch(Node::branch(ll, lrl), Node::branch(lrr, right))
+        }
+    } else if right.height() > left.height() + 1 {
+        let (rl, rr) = right.children();
+        if rr.height() >= rl.height() {
+            Node::branch(Node::branch(left, rl), rr)
+        } else {
+            let (rll, rlr) = rl.children();
+            Node::branch(Node::branch(left, rll), Node::branch(rlr, rr))
+        }
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+// Concatenates two balanced trees into a balanced tree.
+// This takes time proportional to the difference in their heights.
+fn join(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+    if left.height() > right.height() + 1 {
+        let (ll, lr) = left.children();
+        balance(ll, join(lr, right))
+    } else if right.height() > left.height() + 1 {
+        let (rl, rr) = right.children();
+        balance(join(left, rl), rr)
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+fn concat(left: Tree, right: Tree) -> Tree {
+    match (left, right) {
+        (None, tree) | (tree, None) => tree,
+        (Some(left), Some(right)) => Some(join(left, right)),
+    }
+}
+
+// Concatenates two trees, merging the leaves adjacent at the seam
+// into one if the merged leaf does not exceed MAX_LEAF_LEN.
+// Used for edits, so that repeated small edits do not fragment
+// the rope into ever smaller leaves.
+fn concat_merging(left: Tree, right: Tree) -> Tree {
+    let (left, right) = match (left, right) {
+        (None, tree) | (tree, None) => return tree,
+        (Some(left), Some(right)) => (left, right),
+    };
+    let last = left.last_leaf();
+    let first = right.first_leaf();
+    if last.len() + first.len() > MAX_LEAF_LEN {
+        return Some(join(left, right));
+    }
+    let mut merged = StrChunkMut::with_capacity(last.len() + first.len());
+    merged.push_str(last);
+    merged.push_str(first);
+    let merged = Node::leaf(merged.freeze());
+    let (left, _) = split(&left, left.len() - last.len());
+    let (_, right) = split(&right, first.len());
+    concat(concat(left, Some(merged)), right)
+}
+
+// Splits a tree at a byte offset.
+fn split(node: &Arc<Node>, at: usize) -> (Tree, Tree) {
+    if at == 0 {
+        return (None, Some(node.clone()));
+    }
+    if at == node.len() {
+        return (Some(node.clone()), None);
+    }
+    match &**node {
+        Node::Leaf { chunk, .. } => {
+            assert!(
+                chunk.is_char_boundary(at),
+                "byte index {} is not a char boundary",
+                at
+            );
+            let left = Node::leaf(chunk.slice(..at));
+            let right = Node::leaf(chunk.slice(at..));
+            (Some(left), Some(right))
+        }
+        Node::Branch { left, right, .. } => {
+            let left_len = left.len();
+            if at <= left_len {
+                let (ll, lr) = split(left, at);
+                (ll, concat(lr, Some(right.clone())))
+            } else {
+                let (rl, rr) = split(right, at - left_len);
+                (concat(Some(left.clone()), rl), rr)
+            }
+        }
+    }
+}
+
+// Builds a balanced tree from a sequence of non-empty leaves.
+fn build(leaves: &[StrChunk]) -> Tree {
+    match leaves.len() {
+        0 => None,
+        1 => Some(Node::leaf(leaves[0].clone())),
+        n => {
+            let (left, right) = leaves.split_at(n / 2);
+            concat(build(left), build(right))
+        }
+    }
+}
+
+// Splits a chunk into leaves of bounded length.
+fn push_leaves(leaves: &mut Vec<StrChunk>, mut chunk: StrChunk) {
+    while chunk.len() > MAX_LEAF_LEN {
+        let mut at = MAX_LEAF_LEN;
+        while !chunk.is_char_boundary(at) {
+            at -= 1;
+        }
+        leaves.push(chunk.split_to(at));
+    }
+    if !chunk.is_empty() {
+        leaves.push(chunk);
+    }
+}
+
+/// A string represented as a balanced tree of `StrChunk` pieces.
+///
+/// `Rope` supports insertion, removal, and slicing by byte offset or
+/// by char offset in `O(log n)` time, with the pieces of the string
+/// outside of the edited range being shared rather than copied.
+/// Small pieces adjacent to the site of an edit are merged together,
+/// so that repeated small edits do not fragment the rope.
+/// Char offsets can be converted to byte offsets and back with
+/// the `char_to_byte` and `byte_to_char` methods, also in logarithmic
+/// time. Cloning a rope is `O(1)`.
+///
+/// # Example
+///
+/// ```rust
+/// use strchunk::rope::Rope;
+/// use strchunk::StrChunk;
+///
+/// let mut rope = Rope::from(StrChunk::from("Hello, world!"));
+/// rope.insert_str(7, "wonderful ");
+/// rope.remove(..5);
+/// rope.insert_str(0, "Привет");
+/// assert_eq!(rope, "Привет, wonderful world!");
+/// assert_eq!(rope.len_chars(), 24);
+/// assert_eq!(rope.char_to_byte(8), 14);
+/// rope.remove_chars(8..18);
+/// rope.insert_str_at_char(8, "big ");
+/// assert_eq!(rope.slice_chars(8..), "big world!");
+/// ```
+#[derive(Clone, Default)]
+pub struct Rope {
+    root: Tree,
+}
+
+impl Rope {
+    /// Creates a new empty `Rope`.
+    #[inline]
+    pub fn new() -> Self {
+        Rope { root: None }
+    }
+
+    /// Returns the length of the string in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.root.as_ref().map_or(0, |node| node.len())
+    }
+
+    /// Returns the length of the string in chars.
+    #[inline]
+    pub fn len_chars(&self) -> usize {
+        self.root.as_ref().map_or(0, |node| node.chars())
+    }
+
+    /// Returns true if the string is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Converts a char offset into the byte offset of the same
+    /// position in the string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is greater than the length of the string
+    /// in chars.
+    pub fn char_to_byte(&self, char_idx: usize) -> usize {
+        assert!(
+            char_idx <= self.len_chars(),
+            "char index {} is out of bounds of string of {} chars",
+            char_idx,
+            self.len_chars()
+        );
+        let mut node = match &self.root {
+            None => return 0,
+            Some(node) => node,
+        };
+        let mut char_idx = char_idx;
+        let mut byte_idx = 0;
+        loop {
+            match &**node {
+                Node::Leaf { chunk, .. } => {
+                    let offset = chunk
+                        .char_indices()
+                        .nth(char_idx)
+                        .map_or(chunk.len(), |(i, _)| i);
+                    return byte_idx + offset;
+                }
+                Node::Branch { left, right, .. } => {
+                    if char_idx < left.chars() {
+                        node = left;
+                    } else {
+                        char_idx -= left.chars();
+                        byte_idx += left.len();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Converts a byte offset into the char offset of the same
+    /// position in the string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is greater than the length of the string,
+    /// or if it does not lie on a UTF-8 code point boundary.
+    pub fn byte_to_char(&self, byte_idx: usize) -> usize {
+        assert!(
+            byte_idx <= self.len(),
+            "byte index {} is out of bounds of string of length {}",
+            byte_idx,
+            self.len()
+        );
+        let mut node = match &self.root {
+            None => return 0,
+            Some(node) => node,
+        };
+        let mut byte_idx = byte_idx;
+        let mut char_idx = 0;
+        loop {
+            match &**node {
+                Node::Leaf { chunk, .. } => {
+                    return char_idx + chunk[..byte_idx].chars().count();
+                }
+                Node::Branch { left, right, .. } => {
+                    if byte_idx < left.len() {
+                        node = left;
+                    } else {
+                        byte_idx -= left.len();
+                        char_idx += left.chars();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Inserts a `StrChunk` into the string at a byte offset.
+    ///
+    /// The inserted chunk becomes a part of the rope without copying,
+    /// unless it is small enough to be merged with an adjacent piece.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is greater than the length of the string,
+    /// or if it does not lie on a UTF-8 code point boundary.
+    pub fn insert(&mut self, byte_idx: usize, chunk: StrChunk) {
+        self.insert_rope(byte_idx, Rope::from(chunk));
+    }
+
+    /// Inserts a copy of a string slice into the string at a byte offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is greater than the length of the string,
+    /// or if it does not lie on a UTF-8 code point boundary.
+    pub fn insert_str(&mut self, byte_idx: usize, string: &str) {
+        self.insert(byte_idx, StrChunkMut::from(string).freeze());
+    }
+
+    /// Inserts the content of another rope into the string at
+    /// a byte offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_idx` is greater than the length of the string,
+    /// or if it does not lie on a UTF-8 code point boundary.
+    pub fn insert_rope(&mut self, byte_idx: usize, rope: Rope) {
+        let (left, right) = self.split_tree(byte_idx);
+        self.root = concat_merging(concat_merging(left, rope.root), right);
+    }
+
+    /// Inserts a `StrChunk` into the string at a char offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is greater than the length of the string
+    /// in chars.
+    pub fn insert_at_char(&mut self, char_idx: usize, chunk: StrChunk) {
+        self.insert(self.char_to_byte(char_idx), chunk);
+    }
+
+    /// Inserts a copy of a string slice into the string at a char offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is greater than the length of the string
+    /// in chars.
+    pub fn insert_str_at_char(&mut self, char_idx: usize, string: &str) {
+        self.insert_str(self.char_to_byte(char_idx), string);
+    }
+
+    /// Removes the specified byte range from the string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string, if its start
+    /// is greater than its end, or if either of its bounds is not on
+    /// a UTF-8 code point boundary.
+    pub fn remove<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = resolve_range(self.len(), &range);
+        let (left, rest) = self.split_tree(start);
+        let (_, right) = Rope { root: rest }.split_tree(end - start);
+        self.root = concat_merging(left, right);
+    }
+
+    /// Removes the specified char range from the string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string in chars,
+    /// or if its start is greater than its end.
+    pub fn remove_chars<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = self.char_range_to_bytes(&range);
+        self.remove(start..end);
+    }
+
+    /// Returns the specified byte range of the string as a new `Rope`.
+    ///
+    /// The returned rope shares the content with this rope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string, if its start
+    /// is greater than its end, or if either of its bounds is not on
+    /// a UTF-8 code point boundary.
+    pub fn slice<R>(&self, range: R) -> Rope
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = resolve_range(self.len(), &range);
+        let (_, rest) = self.split_tree(start);
+        let (root, _) = Rope { root: rest }.split_tree(end - start);
+        Rope { root }
+    }
+
+    /// Returns the specified char range of the string as a new `Rope`.
+    ///
+    /// The returned rope shares the content with this rope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string in chars,
+    /// or if its start is greater than its end.
+    pub fn slice_chars<R>(&self, range: R) -> Rope
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = self.char_range_to_bytes(&range);
+        self.slice(start..end)
+    }
+
+    /// Splits the string into two at the given byte offset.
+    ///
+    /// Afterwards `self` contains the content before the offset, and
+    /// the returned `Rope` contains the content after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is greater than the length of the string,
+    /// or if it does not lie on a UTF-8 code point boundary.
+    pub fn split_off(&mut self, at: usize) -> Rope {
+        let (left, right) = self.split_tree(at);
+        self.root = left;
+        Rope { root: right }
+    }
+
+    /// Appends the content of another rope to this rope.
+    pub fn append(&mut self, other: Rope) {
+        self.root = concat(self.root.take(), other.root);
+    }
+
+    /// Returns an iterator over the `StrChunk` pieces of the string.
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks {
+            stack: self.root.iter().map(|node| &**node).collect(),
+        }
+    }
+
+    /// Returns an iterator over the chars of the string.
+    pub fn chars(&self) -> Chars<'_> {
+        Chars {
+            chunks: self.chunks(),
+            chars: "".chars(),
+        }
+    }
+
+    /// Returns the content of the rope as a single `StrChunk`.
+    ///
+    /// If the rope consists of a single piece, it is returned without
+    /// copying. Otherwise, the pieces are copied into a new buffer.
+    pub fn to_chunk(&self) -> StrChunk {
+        match self.root.as_deref() {
+            None => StrChunk::new(),
+            Some(Node::Leaf { chunk, .. }) => chunk.clone(),
+            Some(Node::Branch { len, .. }) => {
+                let mut buf = StrChunkMut::with_capacity(*len);
+                for chunk in self.chunks() {
+                    buf.push_str(chunk);
+                }
+                buf.freeze()
+            }
+        }
+    }
+
+    fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.chunks()
+            .flat_map(|chunk| chunk.as_bytes().iter().copied())
+    }
+
+    fn split_tree(&self, at: usize) -> (Tree, Tree) {
+        assert!(
+            at <= self.len(),
+            "byte index {} is out of bounds of string of length {}",
+            at,
+            self.len()
+        );
+        match &self.root {
+            None => (None, None),
+            Some(node) => split(node, at),
+        }
+    }
+
+    fn char_range_to_bytes<R>(&self, range: &R) -> (usize, usize)
+    where
+        R: RangeBounds<usize> + Debug,
+    {
+        let (start, end) = resolve_range(self.len_chars(), range);
+        (self.char_to_byte(start), self.char_to_byte(end))
+    }
+}
+
+impl From<StrChunk> for Rope {
+    fn from(chunk: StrChunk) -> Rope {
+        let mut leaves = Vec::new();
+        push_leaves(&mut leaves, chunk);
+        Rope {
+            root: build(&leaves),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Rope {
+    #[inline]
+    fn from(s: &'a str) -> Rope {
+        Rope::from(StrChunkMut::from(s).freeze())
+    }
+}
+
+impl From<String> for Rope {
+    #[inline]
+    fn from(s: String) -> Rope {
+        Rope::from(StrChunk::from(s))
+    }
+}
+
+impl From<Rope> for StrChunk {
+    #[inline]
+    fn from(rope: Rope) -> StrChunk {
+        rope.to_chunk()
+    }
+}
+
+impl From<Rope> for String {
+    fn from(rope: Rope) -> String {
+        let mut s = String::with_capacity(rope.len());
+        s.extend(rope.chunks().map(|chunk| chunk.as_str()));
+        s
+    }
+}
+
+impl FromIterator<StrChunk> for Rope {
+    fn from_iter<T>(iterable: T) -> Self
+    where
+        T: IntoIterator<Item = StrChunk>,
+    {
+        let mut leaves = Vec::new();
+        for chunk in iterable {
+            push_leaves(&mut leaves, chunk);
+        }
+        Rope {
+            root: build(&leaves),
+        }
+    }
+}
+
+impl Extend<StrChunk> for Rope {
+    fn extend<T>(&mut self, iterable: T)
+    where
+        T: IntoIterator<Item = StrChunk>,
+    {
+        self.append(iterable.into_iter().collect())
+    }
+}
+
+impl Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&String::from(self.clone()), f)
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Rope {
+    fn eq(&self, other: &Rope) -> bool {
+        self.len() == other.len() && self.bytes().eq(other.bytes())
+    }
+}
+
+impl Eq for Rope {}
+
+impl PartialEq<str> for Rope {
+    fn eq(&self, other: &str) -> bool {
+        self.len() == other.len() && self.bytes().eq(other.bytes())
+    }
+}
+
+impl PartialEq<&str> for Rope {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<Rope> for str {
+    #[inline]
+    fn eq(&self, other: &Rope) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialEq<Rope> for &str {
+    #[inline]
+    fn eq(&self, other: &Rope) -> bool {
+        *other == **self
+    }
+}
+
+/// An iterator over the `StrChunk` pieces of a `Rope`.
+///
+/// This struct is created by the `chunks` method of `Rope`.
+#[derive(Clone, Debug)]
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a StrChunk;
+
+    fn next(&mut self) -> Option<&'a StrChunk> {
+        let mut node = self.stack.pop()?;
+        loop {
+            match node {
+                Node::Leaf { chunk, .. } => return Some(chunk),
+                Node::Branch { left, right, .. } => {
+                    self.stack.push(right);
+                    node = left;
+                }
+            }
+        }
+    }
+}
+
+/// An iterator over the chars of a `Rope`.
+///
+/// This struct is created by the `chars` method of `Rope`.
+#[derive(Clone, Debug)]
+pub struct Chars<'a> {
+    chunks: Chunks<'a>,
+    chars: str::Chars<'a>,
+}
+
+impl Iterator for Chars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(c) = self.chars.next() {
+                return Some(c);
+            }
+            self.chars = self.chunks.next()?.chars();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A simple deterministic generator of pseudo-random numbers
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % bound
+        }
+    }
+
+    fn check_balance(node: &Node) {
+        if let Node::Branch {
+            left,
+            right,
+            len,
+            chars,
+            height,
+        } = node
+        {
+            assert!(left.height().abs_diff(right.height()) <= 1);
+            assert_eq!(*height, left.height().max(right.height()) + 1);
+            assert_eq!(*len, left.len() + right.len());
+            assert_eq!(*chars, left.chars() + right.chars());
+            check_balance(left);
+            check_balance(right);
+        }
+    }
+
+    fn random_boundary(rng: &mut Lcg, s: &str) -> usize {
+        let mut at = rng.next(s.len() + 1);
+        while !s.is_char_boundary(at) {
+            at -= 1;
+        }
+        at
+    }
+
+    #[test]
+    fn random_edits() {
+        const WORDS: &[&str] = &["Hello", ", ", "Привет", "🦀", "мир\n", ""];
+        let mut rng = Lcg(42);
+        let mut rope = Rope::new();
+        let mut model = String::new();
+        for _ in 0..2000 {
+            if rng.next(3) == 0 && !model.is_empty() {
+                let a = random_boundary(&mut rng, &model);
+                let b = random_boundary(&mut rng, &model);
+                let (start, end) = (a.min(b), a.max(b));
+                rope.remove(start..end);
+                model.replace_range(start..end, "");
+            } else {
+                let at = random_boundary(&mut rng, &model);
+                let word = WORDS[rng.next(WORDS.len())];
+                rope.insert_str(at, word);
+                model.insert_str(at, word);
+            }
+            assert_eq!(rope.len(), model.len());
+            assert_eq!(rope.len_chars(), model.chars().count());
+        }
+        assert_eq!(rope, *model);
+        assert_eq!(rope.chars().collect::<String>(), model);
+        check_balance(rope.root.as_ref().unwrap());
+        let height = rope.root.as_ref().unwrap().height();
+        let leaves = rope.chunks().count();
+        assert!((1 << (height / 2)) <= leaves, "height {}", height);
+    }
+
+    #[test]
+    fn large_chunk_is_split_into_leaves() {
+        let text = "Привет, мир! ".repeat(1000);
+        let chunk = StrChunk::from(text);
+        let rope = Rope::from(chunk.clone());
+        assert!(rope.chunks().all(|leaf| leaf.len() <= MAX_LEAF_LEN));
+        assert_eq!(rope.chunks().next().unwrap().as_ptr(), chunk.as_ptr());
+        assert_eq!(rope.to_chunk(), chunk);
+        check_balance(rope.root.as_ref().unwrap());
+    }
+
+    #[test]
+    fn char_offsets() {
+        let text = "aПр🦀".repeat(500);
+        let rope = Rope::from(text.as_str());
+        for (char_idx, (byte_idx, _)) in text.char_indices().enumerate() {
+            assert_eq!(rope.char_to_byte(char_idx), byte_idx);
+            assert_eq!(rope.byte_to_char(byte_idx), char_idx);
+        }
+        assert_eq!(rope.char_to_byte(rope.len_chars()), text.len());
+        assert_eq!(rope.byte_to_char(text.len()), rope.len_chars());
+    }
+
+    #[test]
+    fn slice_and_split_share_leaves() {
+        let chunk = StrChunk::from("Hello, world");
+        let mut rope = Rope::from(chunk.clone());
+        let slice = rope.slice(7..);
+        assert_eq!(slice, "world");
+        assert_eq!(slice.to_chunk().as_ptr(), chunk[7..].as_ptr());
+        let tail = rope.split_off(5);
+        assert_eq!(rope, "Hello");
+        assert_eq!(tail, ", world");
+        rope.append(tail);
+        assert_eq!(rope, "Hello, world");
+    }
+
+    #[test]
+    fn small_edits_merge_leaves() {
+        let mut rope = Rope::new();
+        let mut model = String::new();
+        let mut rng = Lcg(7);
+        for i in 0..1000 {
+            let at = rng.next(i + 1);
+            rope.insert_str(at, "x");
+            model.insert(at, 'x');
+        }
+        assert_eq!(rope, *model);
+        assert!(rope.chunks().count() <= 2);
+        assert!(rope.chunks().all(|leaf| leaf.len() <= MAX_LEAF_LEN));
+
+        let mut rope = Rope::from("ab".repeat(MAX_LEAF_LEN).as_str());
+        let leaves = rope.chunks().count();
+        for _ in 0..100 {
+            rope.remove(MAX_LEAF_LEN - 1..MAX_LEAF_LEN + 1);
+        }
+        assert!(rope.chunks().count() <= leaves);
+        check_balance(rope.root.as_ref().unwrap());
+    }
+
+    #[test]
+    fn char_offset_edits() {
+        let mut rope = Rope::from("Привет, мир!");
+        rope.insert_str_at_char(8, "прекрасный ");
+        assert_eq!(rope, "Привет, прекрасный мир!");
+        rope.insert_at_char(6, StrChunk::from(" 🦀"));
+        assert_eq!(rope, "Привет 🦀, прекрасный мир!");
+        assert_eq!(rope.slice_chars(10..=19), "прекрасный");
+        rope.remove_chars(6..8);
+        rope.remove_chars(..8);
+        assert_eq!(rope, "прекрасный мир!");
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_chars_panics_on_oob() {
+        Rope::from("Привет").slice_chars(2..7);
+    }
+
+    #[test]
+    fn clone_is_independent() {
+        let mut rope = Rope::from("Hello");
+        let copy = rope.clone();
+        rope.insert_str(5, ", world");
+        assert_eq!(rope, "Hello, world");
+        assert_eq!(copy, "Hello");
+    }
+
+    #[test]
+    fn collect_chunks() {
+        let rope: Rope = ["Hello", ", ", "", "world"]
+            .iter()
+            .map(|s| StrChunk::from(*s))
+            .collect();
+        assert_eq!(rope.chunks().count(), 3);
+        assert_eq!(rope.to_string(), "Hello, world");
+        assert_eq!(StrChunk::from(rope), "Hello, world");
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_panics_on_split_utf8() {
+        Rope::from("Привет").insert_str(1, "x");
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_panics_on_oob() {
+        Rope::from("Hello").remove(2..6);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn remove_panics_on_inclusive_max() {
+        Rope::from("Hello").remove(2..=usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn slice_panics_on_inclusive_max() {
+        Rope::from("Hello").slice(..=usize::MAX);
+    }
+}
@@ -0,0 +1,459 @@
+//! Adapters for asynchronous I/O with `tokio`.
+//!
+//! This module is available with the `tokio` feature enabled.
+
+use crate::list::StrChunkList;
+use crate::reader::{async_utf8_reader, ReadBuffer};
+use crate::StrChunk;
+
+use bytes::{Buf, BufMut, BytesMut};
+use futures_sink::Sink;
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use std::io::{self, IoSlice};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+async_utf8_reader! {
+    /// Decodes UTF-8 text read from an `AsyncRead` source.
+    ///
+    /// `Utf8Reader` reads input into an internal buffer and extracts
+    /// the complete UTF-8 content from it as `StrChunk` values without
+    /// copying, using a `Utf8Decoder`. A UTF-8 sequence split between
+    /// reads is carried over to be completed by the next read, and
+    /// the bytes preceding it are not validated again.
+    ///
+    /// The reader also implements `Stream`, producing the decoded chunks
+    /// until the end of input is reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> std::io::Result<()> {
+    /// use strchunk::tokio::Utf8Reader;
+    ///
+    /// let input: &[u8] = "Привет".as_bytes();
+    /// let mut reader = Utf8Reader::new(input);
+    /// let chunk = reader.read_utf8().await?;
+    /// assert_eq!(chunk, "Привет");
+    /// let chunk = reader.read_utf8().await?;
+    /// assert!(chunk.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub struct Utf8Reader<R: AsyncRead> {
+        poll_decode: poll_decode_read,
+    }
+}
+
+fn poll_decode_read<R: AsyncRead>(
+    mut reader: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuffer,
+) -> Poll<io::Result<StrChunk>> {
+    buf.poll_decode(|dst| poll_read_buf(reader.as_mut(), cx, dst))
+}
+
+fn poll_read_buf<R: AsyncRead>(
+    reader: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    buf: &mut BytesMut,
+) -> Poll<io::Result<usize>> {
+    let bytes_read = {
+        let dst = buf.chunk_mut();
+        // Safety: the uninitialized memory is only written to
+        // by the reader through ReadBuf.
+        let dst = unsafe { dst.as_uninit_slice_mut() };
+        let mut read_buf = ReadBuf::uninit(dst);
+        let ptr = read_buf.filled().as_ptr();
+        ready!(reader.poll_read(cx, &mut read_buf))?;
+        // Make sure the reader did not swap the buffer
+        assert_eq!(ptr, read_buf.filled().as_ptr());
+        read_buf.filled().len()
+    };
+    // Safety: ReadBuf guarantees that this many bytes
+    // have been initialized.
+    unsafe {
+        buf.advance_mut(bytes_read);
+    }
+    Poll::Ready(Ok(bytes_read))
+}
+
+// The default amount of buffered text at which `ChunkWriter`
+// stops accepting more chunks.
+const DEFAULT_HIGH_WATER_MARK: usize = 16 * 1024;
+
+// The maximum number of chunks passed to a single vectored write.
+const MAX_IO_SLICES: usize = 64;
+
+pin_project! {
+    /// Writes `StrChunk` values to an `AsyncWrite` sink.
+    ///
+    /// `ChunkWriter` implements `Sink<StrChunk>`, queueing the chunks
+    /// without copying and writing them out in batches with
+    /// `poll_write_vectored`. When the amount of queued text reaches
+    /// the high-water mark, the writer stops accepting chunks until
+    /// enough of the queue has been written to fall below it.
+    /// Closing the sink flushes the queued text and shuts down
+    /// the underlying writer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> std::io::Result<()> {
+    /// use futures::stream::{self, StreamExt};
+    /// use strchunk::tokio::ChunkWriter;
+    /// use strchunk::StrChunk;
+    ///
+    /// let chunks = ["Hello", ", ", "world"].map(StrChunk::from);
+    /// let mut writer = ChunkWriter::new(Vec::new());
+    /// stream::iter(chunks).map(Ok).forward(&mut writer).await?;
+    /// assert_eq!(writer.into_inner(), b"Hello, world");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[derive(Debug)]
+    pub struct ChunkWriter<W> {
+        #[pin]
+        inner: W,
+        queue: StrChunkList,
+        high_water_mark: usize,
+    }
+}
+
+impl<W> ChunkWriter<W> {
+    /// Creates a new `ChunkWriter` with the default high-water mark.
+    ///
+    /// The default high-water mark is currently 16 KiB, but this may
+    /// change in the future.
+    pub fn new(inner: W) -> Self {
+        ChunkWriter::with_high_water_mark(DEFAULT_HIGH_WATER_MARK, inner)
+    }
+
+    /// Creates a new `ChunkWriter` with the specified high-water mark.
+    ///
+    /// The writer is ready to accept a chunk while the length of
+    /// the queued text is less than `high_water_mark`. A single chunk
+    /// can be queued regardless of its length, so a high-water mark
+    /// of 0 makes the writer write out each chunk before accepting
+    /// the next one.
+    pub fn with_high_water_mark(high_water_mark: usize, inner: W) -> Self {
+        ChunkWriter {
+            inner,
+            queue: StrChunkList::new(),
+            high_water_mark,
+        }
+    }
+
+    /// Returns the length of the queued text that has not yet been
+    /// written.
+    pub fn buffered_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Writing directly to the underlying writer would interleave
+    /// the output with the queued text.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Gets a pinned mutable reference to the underlying writer.
+    ///
+    /// Writing directly to the underlying writer would interleave
+    /// the output with the queued text.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().inner
+    }
+
+    /// Consumes the `ChunkWriter`, returning the underlying writer.
+    ///
+    /// Any text queued but not yet written is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: AsyncWrite> ChunkWriter<W> {
+    // Writes out the queue until no more than `limit` bytes remain.
+    fn poll_write_queue(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        limit: usize,
+    ) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+        while this.queue.len() > limit {
+            let mut slices = [IoSlice::new(&[]); MAX_IO_SLICES];
+            let n = this.queue.chunks_vectored(&mut slices);
+            let bytes_written = ready!(this
+                .inner
+                .as_mut()
+                .poll_write_vectored(cx, &slices[..n]))?;
+            if bytes_written == 0 {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write queued text",
+                )));
+            }
+            // A partial write may end in the middle of a chunk
+            this.queue.advance(bytes_written);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite> Sink<StrChunk> for ChunkWriter<W> {
+    type Error = io::Error;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let limit = self.high_water_mark.saturating_sub(1);
+        self.poll_write_queue(cx, limit)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: StrChunk) -> io::Result<()> {
+        self.project().queue.push(item);
+        Ok(())
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_write_queue(cx, 0))?;
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_write_queue(cx, 0))?;
+        self.project().inner.poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExtractUtf8Error;
+
+    use futures::sink::SinkExt;
+    use futures::stream::TryStreamExt;
+    use futures::task::noop_waker_ref;
+
+    // Returns the input in pieces of the given size.
+    struct Fragmented {
+        data: &'static [u8],
+        piece_len: usize,
+    }
+
+    impl AsyncRead for Fragmented {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let len = self.piece_len.min(self.data.len()).min(buf.remaining());
+            let (piece, rest) = self.data.split_at(len);
+            buf.put_slice(piece);
+            self.data = rest;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn read_split_sequences() {
+        let input = Fragmented {
+            data: "Привет".as_bytes(),
+            piece_len: 3,
+        };
+        let mut reader = Utf8Reader::new(input);
+        let mut text = String::new();
+        loop {
+            let chunk = reader.read_utf8().await.unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            text.push_str(&chunk);
+        }
+        assert_eq!(text, "Привет");
+    }
+
+    #[tokio::test]
+    async fn read_with_small_capacity() {
+        let input: &[u8] = "Привет, мир!".as_bytes();
+        let reader = Utf8Reader::with_capacity(5, input);
+        let chunks: Vec<StrChunk> = reader.try_collect().await.unwrap();
+        assert_eq!(chunks.concat(), "Привет, мир!");
+    }
+
+    #[tokio::test]
+    async fn incomplete_sequence_at_eof() {
+        let input: &[u8] = b"Hello\xD0";
+        let mut reader = Utf8Reader::new(input);
+        assert_eq!(reader.read_utf8().await.unwrap(), "Hello");
+        let err = reader.read_utf8().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(reader.read_utf8().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn resume_after_invalid_sequence() {
+        let input: &[u8] = b"Hello \xF0\x90\x80World";
+        let mut reader = Utf8Reader::new(input);
+        let err = reader.read_utf8().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = err
+            .into_inner()
+            .unwrap()
+            .downcast::<ExtractUtf8Error>()
+            .unwrap();
+        assert_eq!(err.into_extracted(), "Hello ");
+        assert_eq!(reader.read_utf8().await.unwrap(), "World");
+    }
+
+    #[tokio::test]
+    async fn stream_ends_at_eof() {
+        let input: &[u8] = b"Hello";
+        let reader = Utf8Reader::new(input);
+        let chunks: Vec<StrChunk> = reader.try_collect().await.unwrap();
+        assert_eq!(chunks, ["Hello"]);
+    }
+
+    #[test]
+    fn into_inner() {
+        let input: &[u8] = b"Hello";
+        let reader = Utf8Reader::new(input);
+        assert_eq!(*reader.get_ref(), b"Hello");
+        assert_eq!(reader.into_inner(), b"Hello");
+    }
+
+    // Accepts a limited number of bytes per write, or none at all
+    // while blocked.
+    #[derive(Default)]
+    struct Throttled {
+        data: Vec<u8>,
+        max_write_len: usize,
+        blocked: bool,
+        write_count: usize,
+        shut_down: bool,
+    }
+
+    impl AsyncWrite for Throttled {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.poll_write_vectored(cx, &[IoSlice::new(buf)])
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            if self.blocked {
+                return Poll::Pending;
+            }
+            self.write_count += 1;
+            let mut len = 0;
+            for buf in bufs {
+                let n = buf.len().min(self.max_write_len - len);
+                self.data.extend_from_slice(&buf[..n]);
+                len += n;
+            }
+            Poll::Ready(Ok(len))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            true
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.shut_down = true;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn write_with_partial_writes() {
+        let output = Throttled {
+            max_write_len: 4,
+            ..Default::default()
+        };
+        let mut writer = ChunkWriter::new(output);
+        for s in ["Привет", ", ", "мир", "!"] {
+            writer.feed(StrChunk::from(s)).await.unwrap();
+        }
+        assert_eq!(writer.buffered_len(), 21);
+        writer.close().await.unwrap();
+        let output = writer.into_inner();
+        assert_eq!(output.data, "Привет, мир!".as_bytes());
+        assert_eq!(output.write_count, 6);
+        assert!(output.shut_down);
+    }
+
+    #[tokio::test]
+    async fn vectored_writes_batch_chunks() {
+        let output = Throttled {
+            max_write_len: usize::MAX,
+            ..Default::default()
+        };
+        let mut writer = ChunkWriter::new(output);
+        for s in ["Hello", ", ", "world"] {
+            writer.feed(StrChunk::from(s)).await.unwrap();
+        }
+        writer.flush().await.unwrap();
+        assert_eq!(writer.buffered_len(), 0);
+        let output = writer.into_inner();
+        assert_eq!(output.data, b"Hello, world");
+        assert_eq!(output.write_count, 1);
+        assert!(!output.shut_down);
+    }
+
+    #[test]
+    fn backpressure() {
+        let output = Throttled {
+            max_write_len: usize::MAX,
+            blocked: true,
+            ..Default::default()
+        };
+        let mut writer = ChunkWriter::with_high_water_mark(10, output);
+        let mut writer = Pin::new(&mut writer);
+        let mut cx = Context::from_waker(noop_waker_ref());
+        for s in ["Hello", ", "] {
+            assert!(writer.as_mut().poll_ready(&mut cx).is_ready());
+            writer.as_mut().start_send(StrChunk::from(s)).unwrap();
+        }
+        assert!(writer.as_mut().poll_ready(&mut cx).is_ready());
+        writer.as_mut().start_send(StrChunk::from("world")).unwrap();
+        assert!(writer.as_mut().poll_ready(&mut cx).is_pending());
+        writer.as_mut().get_mut().get_mut().blocked = false;
+        assert!(writer.as_mut().poll_ready(&mut cx).is_ready());
+        assert_eq!(writer.buffered_len(), 0);
+        assert_eq!(writer.get_ref().data, b"Hello, world");
+    }
+}